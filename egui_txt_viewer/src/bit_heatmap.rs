@@ -0,0 +1,160 @@
+use eframe::egui;
+
+use crate::{AppState, ParsedFile, format_addr, parse_data_value};
+
+// Fraction of files with each bit set, per compared address
+pub(crate) struct BitHeatmap {
+    revision: u64,
+    // Bits shown per address: widest value rounded up to whole bytes, at least 8
+    width: u32,
+    rows: Vec<BitRow>,
+}
+
+struct BitRow {
+    addr: u64,
+    // Files whose value parsed as a number
+    samples: usize,
+    // ones[bit] = files with that bit set (bit 0 = LSB)
+    ones: Vec<usize>,
+}
+
+impl BitHeatmap {
+    fn compute(files: &[ParsedFile], addrs: &[u64], revision: u64) -> Self {
+        let values: Vec<Vec<u64>> = addrs
+            .iter()
+            .map(|addr| {
+                files
+                    .iter()
+                    .filter_map(|pf| pf.address_to_data.get(addr).and_then(|raw| parse_data_value(raw)))
+                    .collect()
+            })
+            .collect();
+        let widest = values.iter().flatten().map(|v| 64 - v.leading_zeros()).max().unwrap_or(0);
+        let width = widest.div_ceil(8).max(1) * 8;
+        let rows = addrs
+            .iter()
+            .zip(values)
+            .map(|(&addr, vals)| BitRow {
+                addr,
+                samples: vals.len(),
+                ones: (0..width).map(|bit| vals.iter().filter(|v| (*v >> bit) & 1 == 1).count()).collect(),
+            })
+            .collect();
+        Self { revision, width, rows }
+    }
+}
+
+// Addresses without a numeric value in any file: never measured, not "random"
+const NO_DATA_COLOR: egui::Color32 = egui::Color32::from_rgb(150, 150, 150);
+
+// Blue (always 0) -> white (random) -> red (always 1)
+fn bit_color(p: f32) -> egui::Color32 {
+    let lerp = |a: u8, b: u8, t: f32| (a as f32 + (b as f32 - a as f32) * t) as u8;
+    if p < 0.5 {
+        let t = p * 2.0;
+        egui::Color32::from_rgb(lerp(40, 255, t), lerp(90, 255, t), lerp(200, 255, t))
+    } else {
+        let t = (p - 0.5) * 2.0;
+        egui::Color32::from_rgb(lerp(255, 210, t), lerp(255, 40, t), lerp(255, 40, t))
+    }
+}
+
+impl AppState {
+    pub(crate) fn show_bit_heatmap_window(&mut self, ctx: &egui::Context) {
+        let main_rect = ctx.input(|i| i.screen_rect());
+        let mut open = self.show_bit_heatmap;
+        egui::Window::new("Bit Heatmap")
+            .constrain_to(main_rect)
+            .max_size(main_rect.size())
+            .open(&mut open)
+            .show(ctx, |ui| self.bit_heatmap_ui(ui));
+        self.show_bit_heatmap = open;
+    }
+
+    fn bit_heatmap_ui(&mut self, ui: &mut egui::Ui) {
+        if self.files.is_empty() {
+            ui.label("No files loaded.");
+            return;
+        }
+        if self.bit_heatmap.as_ref().is_none_or(|h| h.revision != self.data_revision) {
+            self.bit_heatmap = Some(BitHeatmap::compute(&self.files, &self.intersect_addresses, self.data_revision));
+        }
+        let Some(heatmap) = &self.bit_heatmap else { return };
+
+        // Legend
+        ui.horizontal(|ui| {
+            ui.label("P(bit = 1):");
+            for p in [0.0, 0.25, 0.5, 0.75, 1.0] {
+                let (rect, _) = ui.allocate_exact_size(egui::vec2(14.0, 14.0), egui::Sense::hover());
+                ui.painter().rect_filled(rect, 0.0, bit_color(p));
+                ui.label(format!("{:.0}%", p * 100.0));
+            }
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(14.0, 14.0), egui::Sense::hover());
+            ui.painter().rect_filled(rect, 0.0, NO_DATA_COLOR);
+            ui.label("no data");
+        });
+        ui.label("Blue = stuck at 0, red = stuck at 1, white = random, grey = no numeric value in any file. Click a row to select its address.");
+        ui.separator();
+
+        let cell = 18.0;
+        let label_w = 90.0;
+        let width = heatmap.width as usize;
+        // Bit header, MSB first
+        ui.horizontal(|ui| {
+            ui.add_space(label_w);
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(cell * width as f32, 14.0), egui::Sense::hover());
+            for col in 0..width {
+                let bit = width - 1 - col;
+                let pos = egui::pos2(rect.left() + (col as f32 + 0.5) * cell, rect.center().y);
+                ui.painter().text(pos, egui::Align2::CENTER_CENTER, bit.to_string(), egui::FontId::monospace(9.0), egui::Color32::BLACK);
+            }
+        });
+
+        let selected = self.selected_addr();
+        let mut select = None;
+        egui::ScrollArea::vertical().auto_shrink([false; 2]).max_height(480.0).show_rows(ui, cell, heatmap.rows.len(), |ui, range| {
+            for row in &heatmap.rows[range] {
+                let size = egui::vec2(label_w + cell * width as f32, cell);
+                let (rect, resp) = ui.allocate_exact_size(size, egui::Sense::click());
+                let painter = ui.painter();
+                if selected == Some(row.addr) {
+                    painter.rect_filled(egui::Rect::from_min_size(rect.min, egui::vec2(label_w, cell)), 0.0, ui.visuals().selection.bg_fill);
+                }
+                painter.text(
+                    egui::pos2(rect.left() + 2.0, rect.center().y),
+                    egui::Align2::LEFT_CENTER,
+                    format_addr(row.addr),
+                    egui::FontId::monospace(11.0),
+                    egui::Color32::BLACK,
+                );
+                for col in 0..width {
+                    let bit = width - 1 - col;
+                    let color = if row.samples == 0 { NO_DATA_COLOR } else { bit_color(row.ones[bit] as f32 / row.samples as f32) };
+                    let cell_rect = egui::Rect::from_min_size(egui::pos2(rect.left() + label_w + col as f32 * cell, rect.top()), egui::vec2(cell, cell));
+                    painter.rect_filled(cell_rect.shrink(0.5), 0.0, color);
+                }
+                if let Some(pos) = resp.hover_pos() {
+                    let col = ((pos.x - rect.left() - label_w) / cell).floor();
+                    if col >= 0.0 && (col as usize) < width {
+                        let bit = width - 1 - col as usize;
+                        egui::show_tooltip_at_pointer(ui.ctx(), egui::Id::new("bit_heatmap_tip"), |ui| {
+                            ui.label(format!("Address {}  bit {}", format_addr(row.addr), bit));
+                            if row.samples == 0 {
+                                ui.label("No numeric value in any file");
+                            } else {
+                                ui.label(format!("Set in {} of {} files", row.ones[bit], row.samples));
+                                ui.label(format!("P(1) = {:.1}%", row.ones[bit] as f32 / row.samples as f32 * 100.0));
+                            }
+                        });
+                    }
+                }
+                if resp.clicked() {
+                    select = Some(row.addr);
+                }
+            }
+        });
+        if let Some(addr) = select {
+            self.select_addr(addr);
+        }
+    }
+}
@@ -0,0 +1,323 @@
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont, point};
+use eframe::egui::{self, Color32, Pos2, Rect, Vec2, pos2, vec2};
+use log::{error, info};
+use std::fs;
+use tiny_skia::{FillRule, Paint, PathBuilder, Pixmap, PremultipliedColorU8, Transform};
+
+use crate::{AppState, StatsMetric, format_addr, generate_palette, group_files_by_value, pie_slice};
+
+// Backend-independent drawing primitives; painted by egui on screen and rendered to SVG / PNG on export
+pub(crate) enum Primitive {
+    Rect { rect: Rect, rounding: f32, color: Color32 },
+    Polygon { points: Vec<Pos2>, color: Color32 },
+    Circle { center: Pos2, radius: f32, color: Color32 },
+    // Monospace text anchored at its top-left corner
+    Text { pos: Pos2, text: String, size: f32, color: Color32 },
+}
+
+impl Primitive {
+    fn translated(self, by: Vec2) -> Self {
+        match self {
+            Primitive::Rect { rect, rounding, color } => Primitive::Rect { rect: rect.translate(by), rounding, color },
+            Primitive::Polygon { points, color } => Primitive::Polygon { points: points.into_iter().map(|p| p + by).collect(), color },
+            Primitive::Circle { center, radius, color } => Primitive::Circle { center: center + by, radius, color },
+            Primitive::Text { pos, text, size, color } => Primitive::Text { pos: pos + by, text, size, color },
+        }
+    }
+}
+
+const LABEL_SIZE: f32 = 10.0;
+const LEGEND_SIZE: f32 = 12.0;
+const LEGEND_LINE: f32 = 16.0;
+
+fn text(pos: Pos2, text: String, size: f32) -> Primitive {
+    Primitive::Text { pos, text, size, color: Color32::BLACK }
+}
+
+pub(crate) fn metric_text(count: usize, total: f32, metric: StatsMetric) -> String {
+    match metric {
+        StatsMetric::Percent => format!("{:.1}%", (count as f32 / total) * 100.0),
+        StatsMetric::Count => format!("{}", count),
+    }
+}
+
+// Palette for the value groups with the chart alpha applied
+pub(crate) fn chart_colors(count: usize, alpha: f32) -> Vec<Color32> {
+    let a = (255.0 * alpha) as u8;
+    generate_palette(count).into_iter().map(|c| Color32::from_rgba_unmultiplied(c.r(), c.g(), c.b(), a)).collect()
+}
+
+// Bars by count in a size-sized box at the origin: value below each bar, metric above it
+pub(crate) fn bar_chart(entries: &[(String, usize)], total: f32, metric: StatsMetric, colors: &[Color32], size: Vec2) -> Vec<Primitive> {
+    let mut prims = Vec::new();
+    let max_count = entries.iter().map(|(_, c)| *c as f32).fold(0.0, f32::max).max(1.0);
+    let bar_gap = 6.0;
+    let bar_count = entries.len() as f32;
+    let bar_width = ((size.x - bar_gap * (bar_count + 1.0)) / bar_count).max(2.0);
+    for (idx, (value_label, count)) in entries.iter().enumerate() {
+        let h = (*count as f32 / max_count) * (size.y - 28.0);
+        let left = bar_gap + (bar_width + bar_gap) * idx as f32;
+        let rect = Rect::from_min_size(pos2(left, size.y - h - 4.0), vec2(bar_width, h));
+        prims.push(Primitive::Rect { rect, rounding: 2.0, color: colors[idx % colors.len()] });
+        prims.push(text(pos2(left, size.y - 14.0), value_label.clone(), LABEL_SIZE));
+        prims.push(text(pos2(left, (rect.top() - 12.0).max(2.0)), metric_text(*count, total, metric), LABEL_SIZE));
+    }
+    prims
+}
+
+// Pie by percentage of all files, percentage on each slice
+pub(crate) fn pie_chart(entries: &[(String, usize)], total: f32, colors: &[Color32], size: Vec2) -> Vec<Primitive> {
+    let mut prims = Vec::new();
+    let center = (size / 2.0).to_pos2();
+    let radius = size.min_elem() * 0.45;
+    if entries.len() == 1 {
+        prims.push(Primitive::Circle { center, radius, color: colors[0] });
+        prims.push(text(center, metric_text(entries[0].1, total, StatsMetric::Percent), LABEL_SIZE));
+        return prims;
+    }
+    let mut start_angle: f32 = 0.0;
+    for (i, (_value_label, count)) in entries.iter().enumerate() {
+        let frac = (*count as f32 / total).max(0.0);
+        let end_angle = start_angle + (frac * std::f32::consts::TAU).min(std::f32::consts::TAU - 1e-3);
+        prims.push(Primitive::Polygon { points: pie_slice(center, radius, start_angle, end_angle), color: colors[i % colors.len()] });
+        let mid = (start_angle + end_angle) * 0.5;
+        let label_pos = pos2(center.x + mid.cos() * radius * 0.65, center.y + mid.sin() * radius * 0.65);
+        prims.push(text(label_pos, metric_text(*count, total, StatsMetric::Percent), LABEL_SIZE));
+        start_angle = end_angle;
+    }
+    prims
+}
+
+// Legend lines "■ Value X (metric): files", wrapped to width; returns the primitives and their height
+fn legend(entries: &[(String, usize)], files: &[Vec<&str>], total: f32, metric: StatsMetric, colors: &[Color32], width: f32) -> (Vec<Primitive>, f32) {
+    // Monospace advance is about 0.6 em
+    let max_chars = (((width - 18.0) / (LEGEND_SIZE * 0.6)) as usize).max(16);
+    let mut prims = Vec::new();
+    let mut y = 0.0;
+    for (idx, (value_label, count)) in entries.iter().enumerate() {
+        let line = format!("Value {} ({}): {}", value_label, metric_text(*count, total, metric), files[idx].join(", "));
+        prims.push(Primitive::Rect { rect: Rect::from_min_size(pos2(0.0, y + 2.0), vec2(10.0, 10.0)), rounding: 0.0, color: colors[idx % colors.len()] });
+        let chars: Vec<char> = line.chars().collect();
+        for chunk in chars.chunks(max_chars) {
+            prims.push(text(pos2(18.0, y), chunk.iter().collect(), LEGEND_SIZE));
+            y += LEGEND_LINE;
+        }
+    }
+    (prims, y)
+}
+
+pub(crate) fn paint(painter: &egui::Painter, origin: Pos2, prims: Vec<Primitive>) {
+    let by = origin.to_vec2();
+    for prim in prims {
+        match prim.translated(by) {
+            Primitive::Rect { rect, rounding, color } => {
+                painter.rect_filled(rect, rounding, color);
+            }
+            Primitive::Polygon { points, color } => {
+                painter.add(egui::Shape::convex_polygon(points, color, egui::Stroke::NONE));
+            }
+            Primitive::Circle { center, radius, color } => {
+                painter.circle_filled(center, radius, color);
+            }
+            Primitive::Text { pos, text, size, color } => {
+                painter.text(pos, egui::Align2::LEFT_TOP, text, egui::FontId::monospace(size), color);
+            }
+        }
+    }
+}
+
+// A complete chart image: title, bars, optional pie and the legend on a white background
+pub(crate) struct Figure {
+    size: Vec2,
+    prims: Vec<Primitive>,
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn svg_fill(color: Color32) -> String {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    format!("fill=\"#{:02x}{:02x}{:02x}\" fill-opacity=\"{:.3}\"", r, g, b, a as f32 / 255.0)
+}
+
+impl Figure {
+    pub(crate) fn to_svg(&self) -> String {
+        let mut out = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n<rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n",
+            w = self.size.x,
+            h = self.size.y
+        );
+        for prim in &self.prims {
+            match prim {
+                Primitive::Rect { rect, rounding, color } => out.push_str(&format!(
+                    "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" rx=\"{}\" {}/>\n",
+                    rect.left(),
+                    rect.top(),
+                    rect.width(),
+                    rect.height(),
+                    rounding,
+                    svg_fill(*color)
+                )),
+                Primitive::Polygon { points, color } => {
+                    let pts: Vec<String> = points.iter().map(|p| format!("{:.1},{:.1}", p.x, p.y)).collect();
+                    out.push_str(&format!("<polygon points=\"{}\" {}/>\n", pts.join(" "), svg_fill(*color)));
+                }
+                Primitive::Circle { center, radius, color } => {
+                    out.push_str(&format!("<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" {}/>\n", center.x, center.y, radius, svg_fill(*color)));
+                }
+                // SVG places text on its baseline; shift down by roughly the ascent
+                Primitive::Text { pos, text, size, color } => out.push_str(&format!(
+                    "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"monospace\" font-size=\"{}\" {}>{}</text>\n",
+                    pos.x,
+                    pos.y + size * 0.8,
+                    size,
+                    svg_fill(*color),
+                    xml_escape(text)
+                )),
+            }
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+
+    // Rasterize on the CPU; scale 2.0 doubles the resolution
+    pub(crate) fn to_png(&self, scale: f32) -> anyhow::Result<Vec<u8>> {
+        let (w, h) = ((self.size.x * scale).ceil() as u32, (self.size.y * scale).ceil() as u32);
+        let mut pixmap = Pixmap::new(w, h).ok_or_else(|| anyhow::anyhow!("invalid image size {}x{}", w, h))?;
+        pixmap.fill(tiny_skia::Color::WHITE);
+        let transform = Transform::from_scale(scale, scale);
+        let fonts = egui::FontDefinitions::default();
+        let font_data = fonts.font_data.get("Hack").ok_or_else(|| anyhow::anyhow!("monospace font not available"))?;
+        let font = FontRef::try_from_slice_and_index(&font_data.font, font_data.index)?;
+        for prim in &self.prims {
+            let (path, color) = match prim {
+                Primitive::Rect { rect, rounding, color } => (rounded_rect(*rect, *rounding), *color),
+                Primitive::Polygon { points, color } => {
+                    let mut pb = PathBuilder::new();
+                    for (i, p) in points.iter().enumerate() {
+                        if i == 0 { pb.move_to(p.x, p.y) } else { pb.line_to(p.x, p.y) }
+                    }
+                    pb.close();
+                    (pb.finish(), *color)
+                }
+                Primitive::Circle { center, radius, color } => (PathBuilder::from_circle(center.x, center.y, *radius), *color),
+                Primitive::Text { pos, text, size, color } => {
+                    draw_text(&mut pixmap, &font, *pos * scale, text, size * scale, *color);
+                    continue;
+                }
+            };
+            let Some(path) = path else { continue };
+            let [r, g, b, a] = color.to_srgba_unmultiplied();
+            let mut paint = Paint::default();
+            paint.set_color_rgba8(r, g, b, a);
+            paint.anti_alias = true;
+            pixmap.fill_path(&path, &paint, FillRule::Winding, transform, None);
+        }
+        Ok(pixmap.encode_png()?)
+    }
+}
+
+fn rounded_rect(rect: Rect, rounding: f32) -> Option<tiny_skia::Path> {
+    let r = rounding.min(rect.width() / 2.0).min(rect.height() / 2.0);
+    if r <= 0.0 {
+        return tiny_skia::Rect::from_ltrb(rect.left(), rect.top(), rect.right(), rect.bottom()).map(PathBuilder::from_rect);
+    }
+    let (l, t, rt, b) = (rect.left(), rect.top(), rect.right(), rect.bottom());
+    let mut pb = PathBuilder::new();
+    pb.move_to(l + r, t);
+    pb.line_to(rt - r, t);
+    pb.quad_to(rt, t, rt, t + r);
+    pb.line_to(rt, b - r);
+    pb.quad_to(rt, b, rt - r, b);
+    pb.line_to(l + r, b);
+    pb.quad_to(l, b, l, b - r);
+    pb.line_to(l, t + r);
+    pb.quad_to(l, t, l + r, t);
+    pb.close();
+    pb.finish()
+}
+
+// Blend glyph coverage into the (premultiplied) pixmap
+fn draw_text(pixmap: &mut Pixmap, font: &FontRef, pos: Pos2, text: &str, px: f32, color: Color32) {
+    let scaled = font.as_scaled(PxScale::from(px));
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    let (w, h) = (pixmap.width() as i32, pixmap.height() as i32);
+    let pixels = pixmap.pixels_mut();
+    let mut x = pos.x;
+    for c in text.chars() {
+        let id = font.glyph_id(c);
+        let glyph = id.with_scale_and_position(px, point(x, pos.y + scaled.ascent()));
+        x += scaled.h_advance(id);
+        let Some(outline) = font.outline_glyph(glyph) else { continue };
+        let bounds = outline.px_bounds();
+        outline.draw(|gx, gy, coverage| {
+            let (px, py) = (bounds.min.x as i32 + gx as i32, bounds.min.y as i32 + gy as i32);
+            if px < 0 || py < 0 || px >= w || py >= h {
+                return;
+            }
+            let alpha = coverage.clamp(0.0, 1.0) * a as f32 / 255.0;
+            let dst = &mut pixels[(py * w + px) as usize];
+            // Source over destination, both premultiplied
+            let over = |s: u8, d: u8| (s as f32 * alpha + d as f32 * (1.0 - alpha)).round() as u8;
+            let out_a = over(255, dst.alpha());
+            let channel = |s: u8, d: u8| over(s, d).min(out_a);
+            if let Some(p) = PremultipliedColorU8::from_rgba(channel(r, dst.red()), channel(g, dst.green()), channel(b, dst.blue()), out_a) {
+                *dst = p;
+            }
+        });
+    }
+}
+
+fn save_bytes(default_name: &str, ext: &str, bytes: anyhow::Result<Vec<u8>>) {
+    let bytes = match bytes {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!("Chart export failed: {:?}", e);
+            return;
+        }
+    };
+    if let Some(path) = rfd::FileDialog::new().set_file_name(default_name).add_filter(ext.to_uppercase(), &[ext]).save_file() {
+        if let Err(e) = fs::write(&path, bytes) {
+            error!("Chart export failed: {:?}", e);
+        } else {
+            info!("Exported: {}", path.to_string_lossy());
+        }
+    }
+}
+
+impl AppState {
+    // Statistics chart of the value distribution at addr, laid out for export
+    pub(crate) fn stats_figure(&self, addr: u64, pie: bool) -> Figure {
+        let groups = group_files_by_value(&self.files, addr, self.display_base);
+        let total = self.files.len() as f32;
+        let mut entries: Vec<(String, usize)> = groups.iter().map(|(k, v)| (k.clone(), v.len())).collect();
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let files: Vec<Vec<&str>> = entries.iter().map(|(k, _)| groups[k].iter().map(|&i| self.files[i].file_name.as_str()).collect()).collect();
+        let colors = chart_colors(entries.len(), self.chart_alpha);
+
+        let margin = 12.0;
+        let bar_size = vec2(if pie { 360.0 } else { 580.0 }, 220.0);
+        let width = margin * 2.0 + bar_size.x + if pie { 220.0 } else { 0.0 };
+        let mut prims = vec![text(pos2(margin, margin), format!("Address {} ({} files)", format_addr(addr), self.files.len()), 14.0)];
+        let top = margin + 24.0;
+        prims.extend(bar_chart(&entries, total, self.stats_metric, &colors, bar_size).into_iter().map(|p| p.translated(vec2(margin, top))));
+        if pie {
+            prims.extend(pie_chart(&entries, total, &colors, vec2(220.0, 220.0)).into_iter().map(|p| p.translated(vec2(margin + bar_size.x, top))));
+        }
+        let legend_top = top + bar_size.y + margin;
+        let (legend, legend_height) = legend(&entries, &files, total, self.stats_metric, &colors, width - margin * 2.0);
+        prims.extend(legend.into_iter().map(|p| p.translated(vec2(margin, legend_top))));
+        Figure { size: vec2(width, legend_top + legend_height + margin), prims }
+    }
+
+    pub(crate) fn save_stats_chart(&self, addr: u64, png: bool) {
+        let figure = self.stats_figure(addr, self.show_pie_chart);
+        let name = format!("chart_{}", format_addr(addr));
+        if png {
+            save_bytes(&format!("{}.png", name), "png", figure.to_png(2.0));
+        } else {
+            save_bytes(&format!("{}.svg", name), "svg", Ok(figure.to_svg().into_bytes()));
+        }
+    }
+}
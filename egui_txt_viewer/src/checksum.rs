@@ -0,0 +1,377 @@
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+
+use crate::{AppState, ParsedFile, format_addr, parse_data_value, parse_user_number};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub(crate) enum ChecksumKind {
+    Crc8,
+    Crc16,
+    #[default]
+    Crc32,
+    Sum8,
+    Xor8,
+}
+
+impl ChecksumKind {
+    const ALL: [ChecksumKind; 5] = [ChecksumKind::Crc8, ChecksumKind::Crc16, ChecksumKind::Crc32, ChecksumKind::Sum8, ChecksumKind::Xor8];
+
+    fn label(self) -> &'static str {
+        match self {
+            ChecksumKind::Crc8 => "CRC-8",
+            ChecksumKind::Crc16 => "CRC-16",
+            ChecksumKind::Crc32 => "CRC-32",
+            ChecksumKind::Sum8 => "Sum-8",
+            ChecksumKind::Xor8 => "XOR-8",
+        }
+    }
+
+    // Result width in bits
+    fn width(self) -> u32 {
+        match self {
+            ChecksumKind::Crc16 => 16,
+            ChecksumKind::Crc32 => 32,
+            _ => 8,
+        }
+    }
+
+    fn is_crc(self) -> bool {
+        matches!(self, ChecksumKind::Crc8 | ChecksumKind::Crc16 | ChecksumKind::Crc32)
+    }
+
+    // Default parameters: CRC-8 (SMBus), CRC-16/CCITT-FALSE, CRC-32 (ISO-HDLC)
+    fn preset(self) -> CrcParams {
+        match self {
+            ChecksumKind::Crc8 => CrcParams { width: 8, poly: 0x07, init: 0, refin: false, refout: false, xorout: 0 },
+            ChecksumKind::Crc16 => CrcParams { width: 16, poly: 0x1021, init: 0xFFFF, refin: false, refout: false, xorout: 0 },
+            ChecksumKind::Crc32 => CrcParams { width: 32, poly: 0x04C1_1DB7, init: 0xFFFF_FFFF, refin: true, refout: true, xorout: 0xFFFF_FFFF },
+            ChecksumKind::Sum8 | ChecksumKind::Xor8 => CrcParams { width: 8, poly: 0, init: 0, refin: false, refout: false, xorout: 0 },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct CrcParams {
+    pub(crate) width: u32,
+    pub(crate) poly: u64,
+    pub(crate) init: u64,
+    pub(crate) refin: bool,
+    pub(crate) refout: bool,
+    pub(crate) xorout: u64,
+}
+
+fn reflect(mut v: u64, width: u32) -> u64 {
+    let mut r = 0;
+    for _ in 0..width {
+        r = (r << 1) | (v & 1);
+        v >>= 1;
+    }
+    r
+}
+
+// Bitwise (table-less) CRC following the Rocksoft parameter model
+pub(crate) fn crc(p: &CrcParams, data: &[u8]) -> u64 {
+    let mask = if p.width >= 64 { u64::MAX } else { (1u64 << p.width) - 1 };
+    let top = 1u64 << (p.width - 1);
+    let mut reg = p.init & mask;
+    for &b in data {
+        let byte = if p.refin { reflect(b as u64, 8) } else { b as u64 };
+        for i in (0..8).rev() {
+            let feedback = ((reg & top) != 0) ^ ((byte >> i) & 1 == 1);
+            reg = (reg << 1) & mask;
+            if feedback {
+                reg ^= p.poly & mask;
+            }
+        }
+    }
+    if p.refout {
+        reg = reflect(reg, p.width);
+    }
+    (reg ^ p.xorout) & mask
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ChecksumConfig {
+    pub(crate) kind: ChecksumKind,
+    // User-editable inputs (hex / binary / decimal accepted)
+    pub(crate) start: String,
+    pub(crate) end: String,
+    pub(crate) poly: String,
+    pub(crate) init: String,
+    pub(crate) xorout: String,
+    pub(crate) refin: bool,
+    pub(crate) refout: bool,
+    // Compare against a checksum stored in the dump itself
+    pub(crate) check_stored: bool,
+    pub(crate) stored_addr: String,
+    pub(crate) stored_little_endian: bool,
+}
+
+impl Default for ChecksumConfig {
+    fn default() -> Self {
+        let mut config = Self {
+            kind: ChecksumKind::default(),
+            start: "0x00".to_string(),
+            end: "0xFF".to_string(),
+            poly: String::new(),
+            init: String::new(),
+            xorout: String::new(),
+            refin: false,
+            refout: false,
+            check_stored: false,
+            stored_addr: String::new(),
+            stored_little_endian: false,
+        };
+        config.load_preset();
+        config
+    }
+}
+
+impl ChecksumConfig {
+    fn load_preset(&mut self) {
+        let p = self.kind.preset();
+        let digits = (p.width / 4) as usize;
+        self.poly = format!("0x{:0digits$X}", p.poly);
+        self.init = format!("0x{:0digits$X}", p.init);
+        self.xorout = format!("0x{:0digits$X}", p.xorout);
+        self.refin = p.refin;
+        self.refout = p.refout;
+    }
+
+    fn params(&self) -> Option<CrcParams> {
+        Some(CrcParams {
+            width: self.kind.width(),
+            poly: parse_user_number(&self.poly)?,
+            init: parse_user_number(&self.init)?,
+            refin: self.refin,
+            refout: self.refout,
+            xorout: parse_user_number(&self.xorout)?,
+        })
+    }
+}
+
+pub(crate) struct ChecksumResult {
+    pub(crate) bytes: usize,
+    // Addresses in the range that the file does not contain
+    pub(crate) missing: usize,
+    pub(crate) value: u64,
+    pub(crate) stored: Option<u64>,
+}
+
+impl ChecksumResult {
+    pub(crate) fn matches(&self) -> Option<bool> {
+        self.stored.map(|s| s == self.value)
+    }
+}
+
+fn byte_at(pf: &ParsedFile, addr: u64) -> Option<u8> {
+    pf.address_to_data.get(&addr).and_then(|raw| parse_data_value(raw)).map(|v| (v & 0xFF) as u8)
+}
+
+pub(crate) fn compute_checksum(pf: &ParsedFile, config: &ChecksumConfig) -> Option<ChecksumResult> {
+    let start = parse_user_number(&config.start)?;
+    let end = parse_user_number(&config.end)?;
+    if end < start {
+        return None;
+    }
+    let data: Vec<u8> = pf.address_to_data.range(start..=end).filter_map(|(addr, _)| byte_at(pf, *addr)).collect();
+    let missing = (end - start).saturating_add(1).saturating_sub(data.len() as u64) as usize;
+    let value = match config.kind {
+        ChecksumKind::Sum8 => data.iter().fold(0u8, |acc, b| acc.wrapping_add(*b)) as u64,
+        ChecksumKind::Xor8 => data.iter().fold(0u8, |acc, b| acc ^ b) as u64,
+        _ => crc(&config.params()?, &data),
+    };
+    let stored = if config.check_stored {
+        let base = parse_user_number(&config.stored_addr)?;
+        let n = (config.kind.width() / 8) as u64;
+        let bytes: Option<Vec<u8>> = (0..n).map(|i| base.checked_add(i).and_then(|addr| byte_at(pf, addr))).collect();
+        bytes.map(|mut bytes| {
+            if config.stored_little_endian {
+                bytes.reverse();
+            }
+            bytes.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64)
+        })
+    } else {
+        None
+    };
+    Some(ChecksumResult { bytes: data.len(), missing, value, stored })
+}
+
+// Per-file results for the configuration they were computed with
+pub(crate) struct ChecksumTable {
+    revision: u64,
+    config: ChecksumConfig,
+    results: Vec<Option<ChecksumResult>>,
+}
+
+impl AppState {
+    pub(crate) fn show_checksum_window(&mut self, ctx: &egui::Context) {
+        let main_rect = ctx.input(|i| i.screen_rect());
+        let mut open = self.show_checksum;
+        egui::Window::new("Checksum")
+            .constrain_to(main_rect)
+            .max_size(main_rect.size())
+            .open(&mut open)
+            .show(ctx, |ui| self.checksum_ui(ui));
+        self.show_checksum = open;
+    }
+
+    fn checksum_ui(&mut self, ui: &mut egui::Ui) {
+        let config = &mut self.checksum_config;
+        ui.horizontal(|ui| {
+            ui.label("Algorithm:");
+            for kind in ChecksumKind::ALL {
+                if ui.selectable_value(&mut config.kind, kind, kind.label()).changed() {
+                    config.load_preset();
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Range:");
+            ui.add(egui::TextEdit::singleline(&mut config.start).desired_width(80.0));
+            ui.label("..=");
+            ui.add(egui::TextEdit::singleline(&mut config.end).desired_width(80.0));
+        });
+        if config.kind.is_crc() {
+            ui.horizontal(|ui| {
+                ui.label("Poly:");
+                ui.add(egui::TextEdit::singleline(&mut config.poly).desired_width(90.0));
+                ui.label("Init:");
+                ui.add(egui::TextEdit::singleline(&mut config.init).desired_width(90.0));
+                ui.label("XorOut:");
+                ui.add(egui::TextEdit::singleline(&mut config.xorout).desired_width(90.0));
+                ui.checkbox(&mut config.refin, "RefIn");
+                ui.checkbox(&mut config.refout, "RefOut");
+                if ui.button("Reset").clicked() {
+                    config.load_preset();
+                }
+            });
+        }
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut config.check_stored, "Check stored checksum at");
+            ui.add_enabled(config.check_stored, egui::TextEdit::singleline(&mut config.stored_addr).hint_text("0x0100").desired_width(80.0));
+            ui.add_enabled(config.check_stored, egui::Checkbox::new(&mut config.stored_little_endian, "Little endian"));
+        });
+        if parse_user_number(&config.start).is_none()
+            || parse_user_number(&config.end).is_none()
+            || (config.kind.is_crc() && config.params().is_none())
+            || (config.check_stored && parse_user_number(&config.stored_addr).is_none())
+        {
+            ui.colored_label(egui::Color32::RED, "Enter valid numbers (0x.. hex, 0b.. binary or decimal).");
+            return;
+        }
+
+        ui.separator();
+        if self.checksum_table.as_ref().is_none_or(|t| t.revision != self.data_revision || t.config != self.checksum_config) {
+            self.checksum_table = Some(ChecksumTable {
+                revision: self.data_revision,
+                config: self.checksum_config.clone(),
+                results: self.files.iter().map(|pf| compute_checksum(pf, &self.checksum_config)).collect(),
+            });
+        }
+        let Some(table) = &self.checksum_table else { return };
+        let (config, results) = (&table.config, &table.results);
+        let digits = (config.kind.width() / 4) as usize;
+        TableBuilder::new(ui)
+            .striped(true)
+            .max_scroll_height(360.0)
+            .column(Column::initial(200.0).resizable(true))
+            .column(Column::initial(60.0).resizable(true))
+            .column(Column::initial(60.0).resizable(true))
+            .column(Column::initial(110.0).resizable(true))
+            .column(Column::initial(110.0).resizable(true))
+            .column(Column::remainder())
+            .header(24.0, |mut header| {
+                for label in ["File", "Bytes", "Missing", "Checksum", "Stored", "Match"] {
+                    header.col(|ui| { ui.label(label); });
+                }
+            })
+            .body(|body| {
+                body.rows(20.0, self.files.len(), |mut row| {
+                    let idx = row.index();
+                    row.col(|ui| { ui.label(&self.files[idx].file_name); });
+                    let Some(r) = &results[idx] else {
+                        row.col(|ui| { ui.label("-"); });
+                        return;
+                    };
+                    row.col(|ui| { ui.label(r.bytes.to_string()); });
+                    row.col(|ui| {
+                        if r.missing > 0 {
+                            ui.colored_label(egui::Color32::from_rgb(230, 140, 0), r.missing.to_string());
+                        } else {
+                            ui.label("0");
+                        }
+                    });
+                    row.col(|ui| { ui.monospace(format!("0x{:0digits$X}", r.value)); });
+                    row.col(|ui| { ui.monospace(r.stored.map_or_else(|| "-".to_string(), |s| format!("0x{:0digits$X}", s))); });
+                    row.col(|ui| match r.matches() {
+                        Some(true) => { ui.colored_label(egui::Color32::from_rgb(0, 150, 0), "OK"); }
+                        Some(false) => { ui.colored_label(egui::Color32::RED, "MISMATCH"); }
+                        None => { ui.label("-"); }
+                    });
+                });
+            });
+        if config.check_stored {
+            let stored_at = parse_user_number(&config.stored_addr).map(format_addr).unwrap_or_default();
+            let failed = results.iter().flatten().filter(|r| r.matches() == Some(false)).count();
+            ui.label(format!("Stored checksum at {}: {} of {} files mismatch", stored_at, failed, self.files.len()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsed_file;
+
+    const CHECK: &[u8] = b"123456789";
+
+    #[test]
+    fn presets_match_standard_check_values() {
+        assert_eq!(crc(&ChecksumKind::Crc8.preset(), CHECK), 0xF4);
+        assert_eq!(crc(&ChecksumKind::Crc16.preset(), CHECK), 0x29B1);
+        assert_eq!(crc(&ChecksumKind::Crc32.preset(), CHECK), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn custom_parameters_match_catalogue() {
+        // CRC-16/ARC and CRC-16/XMODEM exercise reflected and zero-init variants
+        let arc = CrcParams { width: 16, poly: 0x8005, init: 0, refin: true, refout: true, xorout: 0 };
+        assert_eq!(crc(&arc, CHECK), 0xBB3D);
+        let xmodem = CrcParams { width: 16, poly: 0x1021, init: 0, refin: false, refout: false, xorout: 0 };
+        assert_eq!(crc(&xmodem, CHECK), 0x31C3);
+        let crc32c = CrcParams { width: 32, poly: 0x1EDC_6F41, init: 0xFFFF_FFFF, refin: true, refout: true, xorout: 0xFFFF_FFFF };
+        assert_eq!(crc(&crc32c, CHECK), 0xE306_9283);
+    }
+
+    #[test]
+    fn sum_and_xor_over_range() {
+        let data: Vec<String> = CHECK.iter().map(|b| format!("{:02X}", b)).collect();
+        let pf = parsed_file(&data.iter().enumerate().map(|(i, d)| (i as u64, d.as_str())).collect::<Vec<_>>());
+        let mut config = ChecksumConfig { kind: ChecksumKind::Sum8, start: "0".into(), end: "8".into(), ..Default::default() };
+        assert_eq!(compute_checksum(&pf, &config).unwrap().value, 0xDD);
+        config.kind = ChecksumKind::Xor8;
+        assert_eq!(compute_checksum(&pf, &config).unwrap().value, 0x31);
+    }
+
+    #[test]
+    fn full_address_range_does_not_overflow() {
+        let pf = parsed_file(&[(0, "12"), (u64::MAX, "34")]);
+        let config = ChecksumConfig {
+            kind: ChecksumKind::Sum8,
+            start: "0".into(),
+            end: "0xFFFFFFFFFFFFFFFF".into(),
+            check_stored: true,
+            stored_addr: "0xFFFFFFFFFFFFFFFF".into(),
+            ..Default::default()
+        };
+        let r = compute_checksum(&pf, &config).unwrap();
+        assert_eq!((r.bytes, r.value), (2, 0x46));
+        assert_eq!(r.missing as u64, u64::MAX - 2);
+        assert_eq!(r.stored, Some(0x34));
+
+        // A multi-byte stored value running past the end of the address space is absent, not a panic
+        let config = ChecksumConfig { kind: ChecksumKind::Crc16, check_stored: true, stored_addr: "0xFFFFFFFFFFFFFFFF".into(), ..Default::default() };
+        assert_eq!(compute_checksum(&pf, &config).unwrap().stored, None);
+    }
+}
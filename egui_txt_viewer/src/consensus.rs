@@ -0,0 +1,268 @@
+use eframe::egui;
+use log::{error, info, warn};
+use std::collections::BTreeMap;
+use std::fs;
+
+use crate::{AppState, DisplayBase, ParsedFile, format_addr, group_files_by_value, write_suffix_code};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ConsensusStatus {
+    // More than half of the files agree
+    Majority,
+    // A single most common value, but held by half of the files or fewer
+    NoMajority,
+    // Two or more values share the top count
+    Tie,
+}
+
+impl ConsensusStatus {
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            ConsensusStatus::Majority => "majority",
+            ConsensusStatus::NoMajority => "no majority",
+            ConsensusStatus::Tie => "tie",
+        }
+    }
+}
+
+pub(crate) struct ConsensusEntry {
+    // Displayed value that won the vote
+    pub(crate) value: String,
+    // Raw data of the first file holding the winning value (used for suffix-code export)
+    pub(crate) raw: String,
+    pub(crate) votes: usize,
+    pub(crate) total: usize,
+    pub(crate) status: ConsensusStatus,
+    // All values ranked by count desc, then value asc
+    pub(crate) candidates: Vec<(String, usize)>,
+}
+
+impl ConsensusEntry {
+    pub(crate) fn agreement(&self) -> f32 {
+        if self.total == 0 { 0.0 } else { self.votes as f32 / self.total as f32 * 100.0 }
+    }
+
+    pub(crate) fn cell_ui(&self, ui: &mut egui::Ui) {
+        let text = format!("{} ({:.0}%)", self.value, self.agreement());
+        let resp = match self.status {
+            ConsensusStatus::Majority => ui.monospace(text),
+            ConsensusStatus::NoMajority => ui.colored_label(egui::Color32::from_rgb(230, 140, 0), egui::RichText::new(text).monospace()),
+            ConsensusStatus::Tie => ui.colored_label(egui::Color32::RED, egui::RichText::new(format!("{} tie", text)).monospace()),
+        };
+        if self.status != ConsensusStatus::Majority {
+            resp.on_hover_text(self.candidates_text());
+        }
+    }
+
+    pub(crate) fn candidates_text(&self) -> String {
+        self.candidates
+            .iter()
+            .map(|(value, count)| format!("{} x{}", value, count))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+// Majority vote over all files at one address
+pub(crate) fn consensus_at(files: &[ParsedFile], addr: u64, display_base: DisplayBase) -> Option<ConsensusEntry> {
+    let groups = group_files_by_value(files, addr, display_base);
+    let mut ranked: Vec<(&String, &Vec<usize>)> = groups.iter().collect();
+    ranked.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(b.0)));
+    let (value, members) = *ranked.first()?;
+    let runner_up = ranked.get(1).map_or(0, |(_, m)| m.len());
+    let status = if members.len() == runner_up {
+        ConsensusStatus::Tie
+    } else if members.len() * 2 > files.len() {
+        ConsensusStatus::Majority
+    } else {
+        ConsensusStatus::NoMajority
+    };
+    let raw = files[members[0]].address_to_data.get(&addr).cloned().unwrap_or_default();
+    Some(ConsensusEntry {
+        value: value.clone(),
+        raw,
+        votes: members.len(),
+        total: files.len(),
+        status,
+        candidates: ranked.iter().map(|(v, m)| ((*v).clone(), m.len())).collect(),
+    })
+}
+
+pub(crate) struct Consensus {
+    revision: u64,
+    display_base: DisplayBase,
+    pub(crate) entries: BTreeMap<u64, ConsensusEntry>,
+}
+
+impl Consensus {
+    fn compute(files: &[ParsedFile], addrs: &[u64], display_base: DisplayBase, revision: u64) -> Self {
+        let entries = addrs
+            .iter()
+            .filter_map(|&addr| consensus_at(files, addr, display_base).map(|e| (addr, e)))
+            .collect();
+        Self { revision, display_base, entries }
+    }
+
+    fn count(&self, status: ConsensusStatus) -> usize {
+        self.entries.values().filter(|e| e.status == status).count()
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("address,consensus,agreement,votes,total,status\n");
+        for (addr, e) in &self.entries {
+            csv.push_str(&format!(
+                "{},{},{:.1}%,{},{},{}\n",
+                format_addr(*addr),
+                e.value,
+                e.agreement(),
+                e.votes,
+                e.total,
+                e.status.label()
+            ));
+        }
+        csv
+    }
+}
+
+impl AppState {
+    pub(crate) fn build_consensus(&mut self) {
+        self.consensus = Some(Consensus::compute(&self.files, &self.intersect_addresses, self.display_base, self.data_revision));
+    }
+
+    // Keep an existing consensus column in sync with loaded files and display base
+    pub(crate) fn refresh_consensus(&mut self) {
+        if let Some(c) = &self.consensus
+            && (c.revision != self.data_revision || c.display_base != self.display_base)
+        {
+            self.build_consensus();
+        }
+    }
+
+    pub(crate) fn show_consensus_window(&mut self, ctx: &egui::Context) {
+        let main_rect = ctx.input(|i| i.screen_rect());
+        let mut open = self.show_consensus;
+        egui::Window::new("Consensus")
+            .constrain_to(main_rect)
+            .max_size(main_rect.size())
+            .open(&mut open)
+            .show(ctx, |ui| self.consensus_ui(ui));
+        self.show_consensus = open;
+    }
+
+    fn consensus_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(consensus) = &self.consensus else {
+            ui.label("No consensus column. Use the Consensus button to build one.");
+            return;
+        };
+        let total = consensus.entries.len();
+        let mean = if total == 0 { 0.0 } else { consensus.entries.values().map(|e| e.agreement()).sum::<f32>() / total as f32 };
+        ui.label(format!("Files: {}  |  Addresses: {}", self.files.len(), total));
+        ui.label(format!(
+            "Majority: {}  |  No majority: {}  |  Ties: {}",
+            consensus.count(ConsensusStatus::Majority),
+            consensus.count(ConsensusStatus::NoMajority),
+            consensus.count(ConsensusStatus::Tie)
+        ));
+        ui.label(format!("Mean agreement: {:.1}%", mean));
+
+        let mut remove = false;
+        ui.horizontal(|ui| {
+            if ui.button("Export Suffix Code").clicked() {
+                self.export_consensus_suffix_code();
+            }
+            if ui.button("Export CSV").clicked() {
+                self.export_consensus_csv();
+            }
+            if ui.button("Remove Column").clicked() {
+                remove = true;
+            }
+        });
+
+        ui.separator();
+        ui.label("Flagged addresses (click to select):");
+        let mut select = None;
+        egui::ScrollArea::vertical().auto_shrink([false; 2]).max_height(300.0).show(ui, |ui| {
+            for (addr, e) in consensus.entries.iter().filter(|(_, e)| e.status != ConsensusStatus::Majority) {
+                ui.horizontal(|ui| {
+                    if ui.selectable_label(false, egui::RichText::new(format_addr(*addr)).monospace()).clicked() {
+                        select = Some(*addr);
+                    }
+                    let color = if e.status == ConsensusStatus::Tie { egui::Color32::RED } else { egui::Color32::from_rgb(230, 140, 0) };
+                    ui.colored_label(color, e.status.label());
+                    ui.monospace(e.candidates_text());
+                });
+            }
+        });
+        if let Some(addr) = select {
+            self.select_addr(addr);
+        }
+        if remove {
+            self.consensus = None;
+            self.show_consensus = false;
+        }
+    }
+
+    fn export_consensus_suffix_code(&self) {
+        let Some(consensus) = &self.consensus else { return };
+        if consensus.entries.is_empty() {
+            warn!("No data to export");
+            return;
+        }
+        let text = write_suffix_code(consensus.entries.iter().map(|(addr, e)| (*addr, e.raw.as_str())));
+        if let Some(path) = rfd::FileDialog::new().set_file_name("consensus.txt").save_file() {
+            if let Err(e) = fs::write(&path, text) {
+                error!("Export failed: {:?}", e);
+            } else {
+                info!("Exported: {}", path.to_string_lossy());
+            }
+        }
+    }
+
+    fn export_consensus_csv(&self) {
+        let Some(consensus) = &self.consensus else { return };
+        if consensus.entries.is_empty() {
+            warn!("No data to export");
+            return;
+        }
+        if let Some(path) = rfd::FileDialog::new().set_file_name("consensus.csv").save_file() {
+            if let Err(e) = fs::write(&path, consensus.to_csv()) {
+                error!("Export failed: {:?}", e);
+            } else {
+                info!("Exported: {}", path.to_string_lossy());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsed_file;
+
+    fn files(values: &[&str]) -> Vec<ParsedFile> {
+        values.iter().map(|v| parsed_file(&[(0x10, v)])).collect()
+    }
+
+    fn status(values: &[&str]) -> ConsensusStatus {
+        consensus_at(&files(values), 0x10, DisplayBase::Hex).unwrap().status
+    }
+
+    #[test]
+    fn classifies_the_vote() {
+        assert_eq!(status(&["01", "0x01", "02"]), ConsensusStatus::Majority);
+        assert_eq!(status(&["01", "01", "02", "03"]), ConsensusStatus::NoMajority);
+        assert_eq!(status(&["01", "02"]), ConsensusStatus::Tie);
+        assert_eq!(status(&["01", "01", "02", "02", "03"]), ConsensusStatus::Tie);
+        assert_eq!(status(&["7E"]), ConsensusStatus::Majority);
+    }
+
+    #[test]
+    fn ranks_candidates_and_keeps_the_winning_raw_value() {
+        let entry = consensus_at(&files(&["02", "01h", "0x01", "02", "01"]), 0x10, DisplayBase::Hex).unwrap();
+        assert_eq!(entry.value, "0x01");
+        assert_eq!(entry.raw, "01h");
+        assert_eq!((entry.votes, entry.total), (3, 5));
+        assert_eq!(entry.candidates, [("0x01".to_string(), 3), ("0x02".to_string(), 2)]);
+        assert!(consensus_at(&[], 0x10, DisplayBase::Hex).is_none());
+    }
+}
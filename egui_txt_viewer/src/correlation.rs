@@ -0,0 +1,238 @@
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use std::collections::HashMap;
+
+use crate::{AppState, DisplayBase, ParsedFile, format_addr, format_data_with_base, parse_data_value};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub(crate) enum LinkMetric {
+    // Normalized mutual information over displayed values, 0..1
+    #[default]
+    MutualInformation,
+    // Pearson correlation over numeric values, -1..1
+    Pearson,
+}
+
+// Per-address value columns (one entry per file) prepared once per data revision
+pub(crate) struct CorrelationCache {
+    revision: u64,
+    display_base: DisplayBase,
+    addrs: Vec<u64>,
+    // Value category id per file, ids assigned in order of first appearance
+    codes: Vec<Vec<u32>>,
+    entropy: Vec<f64>,
+    numeric: Vec<Vec<Option<f64>>>,
+    // Last ranking: the (address, metric) it was computed for and
+    // (linked address, score) pairs ordered by |score| desc
+    ranked_for: Option<(u64, LinkMetric)>,
+    links: Vec<(u64, f64)>,
+}
+
+fn entropy_of(counts: impl Iterator<Item = usize>, total: f64) -> f64 {
+    counts
+        .map(|c| {
+            let p = c as f64 / total;
+            -p * p.log2()
+        })
+        .sum::<f64>()
+        .max(0.0)
+}
+
+fn normalized_mutual_information(x: &[u32], hx: f64, y: &[u32], hy: f64) -> Option<f64> {
+    if hx <= 0.0 || hy <= 0.0 {
+        return None;
+    }
+    let mut joint: HashMap<(u32, u32), usize> = HashMap::new();
+    for pair in x.iter().copied().zip(y.iter().copied()) {
+        *joint.entry(pair).or_default() += 1;
+    }
+    let hxy = entropy_of(joint.into_values(), x.len() as f64);
+    let mi = (hx + hy - hxy).max(0.0);
+    Some((2.0 * mi / (hx + hy)).min(1.0))
+}
+
+fn pearson(x: &[Option<f64>], y: &[Option<f64>]) -> Option<f64> {
+    let pairs: Vec<(f64, f64)> = x.iter().zip(y).filter_map(|(a, b)| Some(((*a)?, (*b)?))).collect();
+    if pairs.len() < 3 {
+        return None;
+    }
+    let n = pairs.len() as f64;
+    let mx = pairs.iter().map(|p| p.0).sum::<f64>() / n;
+    let my = pairs.iter().map(|p| p.1).sum::<f64>() / n;
+    let (mut sxy, mut sxx, mut syy) = (0.0, 0.0, 0.0);
+    for (a, b) in &pairs {
+        sxy += (a - mx) * (b - my);
+        sxx += (a - mx).powi(2);
+        syy += (b - my).powi(2);
+    }
+    (sxx > 0.0 && syy > 0.0).then(|| sxy / (sxx * syy).sqrt())
+}
+
+impl CorrelationCache {
+    fn compute(files: &[ParsedFile], addrs: &[u64], display_base: DisplayBase, revision: u64) -> Self {
+        let total = files.len().max(1) as f64;
+        let mut codes = Vec::with_capacity(addrs.len());
+        let mut entropy = Vec::with_capacity(addrs.len());
+        let mut numeric = Vec::with_capacity(addrs.len());
+        for addr in addrs {
+            let mut ids: HashMap<String, u32> = HashMap::new();
+            let mut counts: Vec<usize> = Vec::new();
+            let mut column = Vec::with_capacity(files.len());
+            let mut values = Vec::with_capacity(files.len());
+            for pf in files {
+                let raw = pf.address_to_data.get(addr).map(String::as_str).unwrap_or("");
+                let next = ids.len() as u32;
+                let id = *ids.entry(format_data_with_base(raw, display_base)).or_insert(next);
+                if id as usize == counts.len() {
+                    counts.push(0);
+                }
+                counts[id as usize] += 1;
+                column.push(id);
+                values.push(parse_data_value(raw).map(|v| v as f64));
+            }
+            entropy.push(entropy_of(counts.into_iter(), total));
+            codes.push(column);
+            numeric.push(values);
+        }
+        Self { revision, display_base, addrs: addrs.to_vec(), codes, entropy, numeric, ranked_for: None, links: Vec::new() }
+    }
+
+    fn rank(&mut self, addr: u64, metric: LinkMetric) -> &[(u64, f64)] {
+        if self.ranked_for != Some((addr, metric)) {
+            let mut links = Vec::new();
+            if let Some(i) = self.addrs.iter().position(|&a| a == addr) {
+                for j in (0..self.addrs.len()).filter(|&j| j != i) {
+                    let score = match metric {
+                        LinkMetric::MutualInformation => {
+                            normalized_mutual_information(&self.codes[i], self.entropy[i], &self.codes[j], self.entropy[j])
+                        }
+                        LinkMetric::Pearson => pearson(&self.numeric[i], &self.numeric[j]),
+                    };
+                    if let Some(score) = score {
+                        links.push((self.addrs[j], score));
+                    }
+                }
+            }
+            links.sort_by(|a, b| b.1.abs().total_cmp(&a.1.abs()).then_with(|| a.0.cmp(&b.0)));
+            self.ranked_for = Some((addr, metric));
+            self.links = links;
+        }
+        &self.links
+    }
+}
+
+impl AppState {
+    pub(crate) fn show_correlation_window(&mut self, ctx: &egui::Context) {
+        let main_rect = ctx.input(|i| i.screen_rect());
+        let mut open = self.show_correlation;
+        egui::Window::new("Correlation")
+            .constrain_to(main_rect)
+            .max_size(main_rect.size())
+            .open(&mut open)
+            .show(ctx, |ui| self.correlation_ui(ui));
+        self.show_correlation = open;
+    }
+
+    fn correlation_ui(&mut self, ui: &mut egui::Ui) {
+        if self.files.len() < 3 {
+            ui.label("Load at least three files to measure co-variation.");
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.label("Metric:");
+            ui.radio_value(&mut self.correlation_metric, LinkMetric::MutualInformation, "Mutual information");
+            ui.radio_value(&mut self.correlation_metric, LinkMetric::Pearson, "Pearson");
+            ui.add(egui::DragValue::new(&mut self.correlation_limit).clamp_range(1..=500).prefix("Top "));
+        });
+        let Some(addr) = self.selected_addr() else {
+            ui.separator();
+            ui.label("Tip: click a row (Address column) to select.");
+            return;
+        };
+        let stale = self
+            .correlation
+            .as_ref()
+            .is_none_or(|c| c.revision != self.data_revision || c.display_base != self.display_base);
+        if stale {
+            self.correlation = Some(CorrelationCache::compute(&self.files, &self.intersect_addresses, self.display_base, self.data_revision));
+        }
+        let Some(cache) = &mut self.correlation else { return };
+        let links = cache.rank(addr, self.correlation_metric);
+        let shown = &links[..links.len().min(self.correlation_limit)];
+
+        ui.separator();
+        ui.label(format!("Addresses most strongly linked to {} (click to follow):", format_addr(addr)));
+        if shown.is_empty() {
+            ui.label("No linked addresses: the value is constant or not numeric.");
+        }
+        let mut select = None;
+        TableBuilder::new(ui)
+            .striped(true)
+            .max_scroll_height(360.0)
+            .column(Column::initial(120.0).resizable(true))
+            .column(Column::initial(80.0).resizable(true))
+            .column(Column::remainder())
+            .header(24.0, |mut header| {
+                header.col(|ui| { ui.label("Address"); });
+                header.col(|ui| { ui.label("Score"); });
+                header.col(|ui| { ui.label("Strength"); });
+            })
+            .body(|body| {
+                body.rows(20.0, shown.len(), |mut row| {
+                    let (linked, score) = shown[row.index()];
+                    row.col(|ui| {
+                        if ui.selectable_label(false, format_addr(linked)).clicked() {
+                            select = Some(linked);
+                        }
+                    });
+                    row.col(|ui| { ui.label(format!("{:+.3}", score)); });
+                    row.col(|ui| {
+                        let (rect, _) = ui.allocate_exact_size(egui::vec2(120.0, 10.0), egui::Sense::hover());
+                        ui.painter().rect_filled(rect, 0.0, egui::Color32::from_gray(230));
+                        let color = if score < 0.0 { egui::Color32::from_rgb(220, 90, 60) } else { egui::Color32::from_rgb(40, 110, 220) };
+                        let bar = egui::Rect::from_min_size(rect.min, egui::vec2(rect.width() * score.abs() as f32, rect.height()));
+                        ui.painter().rect_filled(bar, 0.0, color);
+                    });
+                });
+            });
+        if let Some(linked) = select {
+            self.select_addr(linked);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsed_file;
+
+    fn cache() -> CorrelationCache {
+        // 0x01 doubles 0x00, 0x02 runs backwards, 0x03 never changes, 0x04 splits the files in two
+        let columns = [["01", "02", "03", "04"], ["02", "04", "06", "08"], ["04", "03", "02", "01"], ["01", "01", "01", "01"], ["01", "01", "02", "02"]];
+        let files: Vec<ParsedFile> = (0..4)
+            .map(|f| parsed_file(&columns.iter().enumerate().map(|(addr, c)| (addr as u64, c[f])).collect::<Vec<_>>()))
+            .collect();
+        CorrelationCache::compute(&files, &[0, 1, 2, 3, 4], DisplayBase::Hex, 1)
+    }
+
+    fn scores(links: &[(u64, f64)]) -> Vec<(u64, f64)> {
+        links.iter().map(|&(addr, score)| (addr, (score * 1000.0).round() / 1000.0)).collect()
+    }
+
+    #[test]
+    fn ranks_mutual_information() {
+        let mut cache = cache();
+        assert_eq!(cache.entropy[0], 2.0);
+        assert_eq!(cache.entropy[3], 0.0);
+        assert_eq!(scores(cache.rank(0, LinkMetric::MutualInformation)), [(1, 1.0), (2, 1.0), (4, 0.667)]);
+    }
+
+    #[test]
+    fn ranks_pearson_by_magnitude() {
+        let mut cache = cache();
+        assert_eq!(scores(cache.rank(0, LinkMetric::Pearson)), [(1, 1.0), (2, -1.0), (4, 0.894)]);
+        assert!(cache.rank(0x99, LinkMetric::Pearson).is_empty());
+        // Too few numeric pairs
+        assert_eq!(pearson(&[Some(1.0), Some(2.0), None], &[Some(1.0), Some(2.0), Some(3.0)]), None);
+    }
+}
@@ -0,0 +1,78 @@
+use crate::{DisplayBase, ParsedFile, format_data_with_base, parse_data_value};
+
+// What the data columns show: the raw value or a value derived against a baseline file
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub(crate) enum ValueView {
+    #[default]
+    Raw,
+    // file XOR baseline
+    Xor,
+    // file - baseline, signed
+    Delta,
+}
+
+impl ValueView {
+    pub(crate) const ALL: [ValueView; 3] = [ValueView::Raw, ValueView::Xor, ValueView::Delta];
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            ValueView::Raw => "Raw",
+            ValueView::Xor => "XOR",
+            ValueView::Delta => "Delta",
+        }
+    }
+}
+
+// Format a derived magnitude in the display base (hex digits go through format_data_with_base)
+fn format_magnitude(v: u64, base: DisplayBase) -> String {
+    format_data_with_base(&format!("{:X}", v), base)
+}
+
+// Text shown for a cell and whether it is a zero delta (dimmed in the table).
+// Values that are missing or not numeric on either side are shown as "?".
+pub(crate) fn cell_value(pf: &ParsedFile, baseline: Option<&ParsedFile>, addr: u64, view: ValueView, base: DisplayBase) -> (String, bool) {
+    let raw = pf.address_to_data.get(&addr).map(String::as_str).unwrap_or("");
+    let (ValueView::Xor | ValueView::Delta, Some(baseline)) = (view, baseline) else {
+        return (format_data_with_base(raw, base), false);
+    };
+    let other = baseline.address_to_data.get(&addr).and_then(|r| parse_data_value(r));
+    let Some((v, b)) = parse_data_value(raw).zip(other) else {
+        return ("?".to_string(), false);
+    };
+    match view {
+        ValueView::Xor => (format_magnitude(v ^ b, base), v == b),
+        _ => {
+            let sign = if v > b { "+" } else if v < b { "-" } else { "" };
+            (format!("{}{}", sign, format_magnitude(v.abs_diff(b), base)), v == b)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsed_file;
+
+    #[test]
+    fn derives_against_the_baseline() {
+        let file = parsed_file(&[(0, "0F"), (1, "0x20"), (2, "33")]);
+        let baseline = parsed_file(&[(0, "F0"), (1, "20h")]);
+        let value = |addr, view, base| cell_value(&file, Some(&baseline), addr, view, base);
+        assert_eq!(value(0, ValueView::Xor, DisplayBase::Hex), ("0xff".to_string(), false));
+        assert_eq!(value(0, ValueView::Delta, DisplayBase::Hex), ("-0xe1".to_string(), false));
+        assert_eq!(value(0, ValueView::Delta, DisplayBase::Dec), ("-225".to_string(), false));
+        assert_eq!(value(1, ValueView::Xor, DisplayBase::Hex), ("0x00".to_string(), true));
+        assert_eq!(value(1, ValueView::Delta, DisplayBase::Hex), ("0x00".to_string(), true));
+        assert_eq!(value(0, ValueView::Raw, DisplayBase::Hex), ("0x0f".to_string(), false));
+    }
+
+    #[test]
+    fn missing_values_show_a_question_mark() {
+        let file = parsed_file(&[(0, "0F"), (2, "33")]);
+        let baseline = parsed_file(&[(0, "F0"), (1, "20")]);
+        assert_eq!(cell_value(&file, Some(&baseline), 2, ValueView::Xor, DisplayBase::Hex).0, "?");
+        assert_eq!(cell_value(&file, Some(&baseline), 1, ValueView::Delta, DisplayBase::Hex).0, "?");
+        // Without a baseline every view shows the raw value
+        assert_eq!(cell_value(&file, None, 2, ValueView::Delta, DisplayBase::Hex), ("0x33".to_string(), false));
+    }
+}
@@ -0,0 +1,320 @@
+use log::{error, info, warn};
+use std::collections::BTreeMap;
+use std::fs;
+
+use crate::history::Action;
+use crate::search::parse_in_base;
+use crate::{AppState, ParsedFile, format_addr, format_data_with_base, parse_data_value};
+
+// Cell being edited in the main table (displayed address)
+pub(crate) struct CellEdit {
+    pub(crate) file_idx: usize,
+    pub(crate) addr: u64,
+    pub(crate) text: String,
+    pub(crate) error: Option<String>,
+}
+
+impl ParsedFile {
+    pub(crate) fn is_edited(&self, addr: u64) -> bool {
+        self.original_addr(addr).is_some_and(|a| self.edited.contains(&a))
+    }
+
+    // Source text with the edited addresses written back: changed rows are rewritten in place,
+    // rows of removed addresses dropped and added addresses inserted next to the nearest existing
+    // row, formatted like it. Every other line is kept byte-for-byte. Also returns the new line_of.
+    fn rewrite_source(&self) -> Result<(String, BTreeMap<u64, usize>), String> {
+        let lines: Vec<&str> = self.source.split_inclusive('\n').collect();
+        let newline = if self.source.contains("\r\n") { "\r\n" } else { "\n" };
+        let addr_at: BTreeMap<usize, u64> = self.line_of.iter().map(|(&addr, &line)| (line, addr)).collect();
+        // Added addresses by source line: inserted before / after that line, formatted like its row
+        let mut before: BTreeMap<usize, Vec<(u64, u64)>> = BTreeMap::new();
+        let mut after: BTreeMap<usize, Vec<(u64, u64)>> = BTreeMap::new();
+        for &addr in &self.edited {
+            if self.line_of.contains_key(&addr) || !self.original_data.contains_key(&addr) {
+                continue;
+            }
+            if let Some((&near, &line)) = self.line_of.range(..addr).next_back() {
+                after.entry(line).or_default().push((near, addr));
+            } else if let Some((&near, &line)) = self.line_of.range(addr..).next() {
+                before.entry(line).or_default().push((near, addr));
+            } else {
+                return Err(format!("no data row to copy the layout from for added address {}", format_addr(addr)));
+            }
+        }
+
+        let mut out = String::with_capacity(self.source.len());
+        let mut line_of = BTreeMap::new();
+        // Line breaks written so far = index of the next output line
+        let mut breaks = 0;
+        let mut push = |out: &mut String, addr: Option<u64>, text: &str| {
+            if let Some(addr) = addr {
+                line_of.insert(addr, breaks);
+            }
+            breaks += text.matches('\n').count();
+            out.push_str(text);
+        };
+        let added_row = |near: u64, addr: u64| {
+            let line = lines[self.line_of[&near]];
+            format!("{}{}", rewrite_row(line.trim_end_matches(['\r', '\n']), &self.original_data[&addr], Some((near, addr))), newline)
+        };
+        for (idx, line) in lines.iter().enumerate() {
+            for &(near, addr) in before.get(&idx).into_iter().flatten() {
+                push(&mut out, Some(addr), &added_row(near, addr));
+            }
+            match addr_at.get(&idx) {
+                Some(&addr) if self.edited.contains(&addr) => {
+                    // Removed addresses drop their row
+                    if let Some(data) = self.original_data.get(&addr) {
+                        let body = line.trim_end_matches(['\r', '\n']);
+                        push(&mut out, Some(addr), &format!("{}{}", rewrite_row(body, data, None), &line[body.len()..]));
+                    }
+                }
+                addr => push(&mut out, addr.copied(), line),
+            }
+            for &(near, addr) in after.get(&idx).into_iter().flatten() {
+                if !out.is_empty() && !out.ends_with('\n') {
+                    push(&mut out, None, newline);
+                }
+                push(&mut out, Some(addr), &added_row(near, addr));
+            }
+        }
+        Ok((out, line_of))
+    }
+}
+
+// Address cell of a dump row, read like parse_txt_file: hex with optional 0x / h
+fn parse_addr_cell(cell: &str) -> Option<u64> {
+    u64::from_str_radix(cell.trim().trim_end_matches('h').trim_start_matches("0x"), 16).ok()
+}
+
+// Hex cell split into its 0x prefix, digits and h suffix (marks as written)
+fn split_hex_cell(cell: &str) -> (&str, &str, &str) {
+    let t = cell.trim();
+    let (prefix, rest) = if t.len() >= 2 && t[..2].eq_ignore_ascii_case("0x") { t.split_at(2) } else { ("", t) };
+    match rest.strip_suffix(['h', 'H']) {
+        Some(digits) => (prefix, digits, &rest[digits.len()..]),
+        None => (prefix, rest, ""),
+    }
+}
+
+// value in the style of an existing hex cell: same 0x / h marks, digit case and at least its
+// digit count (never fewer than min_width)
+fn format_like(cell: &str, value: u64, min_width: usize) -> String {
+    let (prefix, digits, suffix) = split_hex_cell(cell);
+    let width = digits.len().max(min_width);
+    if digits.chars().any(|c| c.is_ascii_lowercase()) {
+        format!("{}{:0width$x}{}", prefix, value, suffix)
+    } else {
+        format!("{}{:0width$X}{}", prefix, value, suffix)
+    }
+}
+
+// One data row (without line ending) with new data, written in the style of the row's data cell;
+// with (old, new) addresses the address columns (3 to 5, as parse_txt_file reads them) holding
+// the old address are rewritten to the new one, for rows copied from a neighbour
+fn rewrite_row(body: &str, data: &str, addr: Option<(u64, u64)>) -> String {
+    // Columns are counted on the trimmed line like parse_txt_file does; the surrounding
+    // whitespace is written back unchanged
+    let trimmed = body.trim();
+    let leading = &body[..body.len() - body.trim_start().len()];
+    let trailing = &body[body.trim_end().len()..];
+    let mut parts: Vec<String> = trimmed.split('\t').map(str::to_string).collect();
+    if let Some((old, new)) = addr {
+        for (idx, cell) in parts.iter_mut().enumerate() {
+            if (2..=4).contains(&idx) && parse_addr_cell(cell) == Some(old) {
+                *cell = format_like(cell, new, 0);
+            }
+        }
+    }
+    // Column 6 holds the data, column 7 (when present) its decimal value
+    if let Some(cell) = parts.get_mut(5) {
+        *cell = parse_data_value(data).map_or_else(|| data.to_string(), |v| format_like(cell, v, 0));
+    }
+    if let (Some(cell), Some(v)) = (parts.get_mut(6), parse_data_value(data))
+        && !cell.trim().is_empty()
+    {
+        *cell = v.to_string();
+    }
+    format!("{}{}{}", leading, parts.join("\t"), trailing)
+}
+
+impl AppState {
+    pub(crate) fn begin_edit(&mut self, file_idx: usize, addr: u64) {
+        let Some(raw) = self.files.get(file_idx).and_then(|pf| pf.address_to_data.get(&addr)) else { return };
+        let text = format_data_with_base(raw, self.display_base);
+        self.editing = Some(CellEdit { file_idx, addr, text, error: None });
+    }
+
+    // Validate the edited text in the current display base and store it in the file's layout
+    pub(crate) fn commit_edit(&mut self) {
+        let Some(edit) = &mut self.editing else { return };
+        let Some(pf) = self.files.get_mut(edit.file_idx) else {
+            self.editing = None;
+            return;
+        };
+        // The address can vanish from the layout (e.g. an offset or range change) while editing
+        let Some(original) = pf.original_addr(edit.addr) else {
+            self.editing = None;
+            return;
+        };
+        let old = pf.original_data.get(&original).cloned().unwrap_or_default();
+        // Keep the original digit count (at least two hex digits)
+        let digits = split_hex_cell(&old).1.len().max(2);
+        let max = if digits >= 16 { u64::MAX } else { (1u64 << (digits * 4)) - 1 };
+        let value = match parse_in_base(&edit.text, self.display_base) {
+            Some(v) if v <= max => v,
+            Some(_) => {
+                edit.error = Some(format!("Value does not fit in {} hex digits", digits));
+                return;
+            }
+            None => {
+                edit.error = Some(format!("Not a valid {:?} number", self.display_base));
+                return;
+            }
+        };
+        // Same 0x / h marks and case as the old value, so the saved line keeps its layout
+        let data = format_like(&old, value, digits);
+        let file_idx = edit.file_idx;
+        if parse_data_value(&old) != Some(value) {
+            info!("Edited {} at {}: {} -> {}", pf.file_name, format_addr(original), old, data);
+            let was_edited = pf.edited.contains(&original);
+            pf.original_data.insert(original, data.clone());
+            pf.address_to_data.insert(edit.addr, data.clone());
+            pf.edited.insert(original);
+            self.record(Action::Edit { file_idx, original, before: (old, was_edited), after: (data, true) });
+            self.recalc_intersection();
+        }
+        self.editing = None;
+    }
+
+    pub(crate) fn save_file(&mut self, file_idx: usize) {
+        let Some(pf) = self.files.get(file_idx) else { return };
+        if pf.source.is_empty() {
+            warn!("{} has no source text to save back into", pf.file_name);
+            return;
+        }
+        let (text, line_of) = match pf.rewrite_source() {
+            Ok(rewritten) => rewritten,
+            Err(e) => {
+                warn!("Cannot save {}: {}", pf.file_name, e);
+                return;
+            }
+        };
+        let mut dialog = rfd::FileDialog::new().set_file_name(&pf.file_name);
+        if let Some(dir) = pf.path.as_ref().and_then(|p| p.parent()) {
+            dialog = dialog.set_directory(dir);
+        }
+        let Some(path) = dialog.save_file() else { return };
+        if let Err(e) = fs::write(&path, &text) {
+            error!("Save failed: {:?}", e);
+            return;
+        }
+        info!("Saved: {}", path.to_string_lossy());
+        let pf = &mut self.files[file_idx];
+        pf.source = text;
+        pf.line_of = line_of;
+        pf.edited.clear();
+        pf.path = Some(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsed_file;
+
+    const SOURCE: &str = "Tester A\r\n0001\t0\t02\t02\t02h\t7E\t126\t\r\n0002\t0\t03\t03\t03h\t10\t16\t\r\nEND\r\n";
+
+    // File as parse_txt_file would load it, with data rows at the given (address, line) pairs
+    fn loaded(source: &str, rows: &[(u64, usize, &str)]) -> ParsedFile {
+        let data: Vec<(u64, &str)> = rows.iter().map(|&(addr, _, data)| (addr, data)).collect();
+        let mut pf = ParsedFile { source: source.to_string(), ..parsed_file(&data) };
+        pf.line_of = rows.iter().map(|&(addr, line, _)| (addr, line)).collect();
+        pf
+    }
+
+    fn set(pf: &mut ParsedFile, addr: u64, data: Option<&str>) {
+        match data {
+            Some(d) => pf.original_data.insert(addr, d.to_string()),
+            None => pf.original_data.remove(&addr),
+        };
+        pf.edited.insert(addr);
+    }
+
+    #[test]
+    fn rewrite_keeps_other_lines_and_line_endings() {
+        let mut pf = loaded(SOURCE, &[(0x02, 1, "7E"), (0x03, 2, "10")]);
+        set(&mut pf, 0x02, Some("7F"));
+        let (text, line_of) = pf.rewrite_source().unwrap();
+        assert_eq!(text, SOURCE.replacen("7E\t126", "7F\t127", 1));
+        assert_eq!(line_of, pf.line_of);
+    }
+
+    #[test]
+    fn rewrite_counts_columns_after_leading_whitespace() {
+        // parse_txt_file trims the line before splitting, so the indentation must not shift columns
+        let mut pf = loaded("Tester A\n\t 0001\t0\t02\t02\t02h\t7E\t126\t \n", &[(0x02, 1, "7E")]);
+        set(&mut pf, 0x02, Some("05"));
+        assert_eq!(pf.rewrite_source().unwrap().0, "Tester A\n\t 0001\t0\t02\t02\t02h\t05\t5\t \n");
+    }
+
+    #[test]
+    fn rewrite_drops_removed_rows() {
+        let mut pf = loaded(SOURCE, &[(0x02, 1, "7E"), (0x03, 2, "10")]);
+        set(&mut pf, 0x02, None);
+        let (text, line_of) = pf.rewrite_source().unwrap();
+        assert_eq!(text, "Tester A\r\n0002\t0\t03\t03\t03h\t10\t16\t\r\nEND\r\n");
+        assert_eq!(line_of, BTreeMap::from([(0x03, 1)]));
+    }
+
+    #[test]
+    fn rewrite_inserts_added_rows_next_to_their_neighbours() {
+        let mut pf = loaded(SOURCE, &[(0x02, 1, "7E"), (0x03, 2, "10")]);
+        set(&mut pf, 0x01, Some("AA"));
+        set(&mut pf, 0x1F, Some("05"));
+        let (text, line_of) = pf.rewrite_source().unwrap();
+        assert_eq!(
+            text,
+            "Tester A\r\n0001\t0\t01\t01\t01h\tAA\t170\t\r\n0001\t0\t02\t02\t02h\t7E\t126\t\r\n\
+             0002\t0\t03\t03\t03h\t10\t16\t\r\n0002\t0\t1F\t1F\t1Fh\t05\t5\t\r\nEND\r\n"
+        );
+        assert_eq!(line_of, BTreeMap::from([(0x01, 1), (0x02, 2), (0x03, 3), (0x1F, 4)]));
+    }
+
+    #[test]
+    fn rewrite_appends_after_a_last_line_without_break() {
+        let mut pf = loaded("0001\t0\t0x0e\t0\t0\t7E", &[(0x0E, 0, "7E")]);
+        set(&mut pf, 0x10, Some("01"));
+        assert_eq!(pf.rewrite_source().unwrap().0, "0001\t0\t0x0e\t0\t0\t7E\n0001\t0\t0x10\t0\t0\t01\n");
+    }
+
+    #[test]
+    fn rewrite_needs_a_row_to_copy_for_added_addresses() {
+        let mut pf = loaded("Tester A\n", &[]);
+        set(&mut pf, 0x10, Some("01"));
+        assert!(pf.rewrite_source().is_err());
+    }
+
+    #[test]
+    fn copied_rows_keep_the_sequence_column() {
+        // Sequence "0001" equals the neighbour's address 0x01; only columns 3 to 5 are addresses
+        let mut pf = loaded("0001\t0\t01\t01\t01h\t7E\t126\t\n", &[(0x01, 0, "7E")]);
+        set(&mut pf, 0x02, Some("05"));
+        assert_eq!(pf.rewrite_source().unwrap().0, "0001\t0\t01\t01\t01h\t7E\t126\t\n0001\t0\t02\t02\t02h\t05\t5\t\n");
+    }
+
+    #[test]
+    fn edits_keep_the_data_cell_format() {
+        for (written, typed, stored, saved) in [("0x7e", "7f", "0x7f", "0x7f"), ("7Eh", "0x1", "01h", "01h"), ("7E", "80", "80", "80")] {
+            let source = format!("0001\t0\t02\t02\t02h\t{}\t126\t\n", written);
+            let mut app = AppState::default();
+            app.files.push(loaded(&source, &[(0x02, 0, written)]));
+            app.begin_edit(0, 0x02);
+            app.editing.as_mut().unwrap().text = typed.to_string();
+            app.commit_edit();
+            assert_eq!(app.files[0].original_data[&0x02], stored);
+            let value = parse_data_value(stored).unwrap();
+            assert_eq!(app.files[0].rewrite_source().unwrap().0, format!("0001\t0\t02\t02\t02h\t{}\t{}\t\n", saved, value));
+        }
+    }
+}
@@ -0,0 +1,600 @@
+use eframe::egui;
+use log::{error, info, warn};
+use rust_xlsxwriter::{Color, Format, Workbook};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::consensus::ConsensusStatus;
+use crate::{AppState, DisplayBase, ParsedFile, csv_field, derived, format_addr, is_data_different};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub(crate) enum ExportFormat {
+    #[default]
+    Csv,
+    Tsv,
+    Json,
+    Xlsx,
+    Html,
+    Markdown,
+}
+
+impl ExportFormat {
+    const ALL: [ExportFormat; 6] = [
+        ExportFormat::Csv,
+        ExportFormat::Tsv,
+        ExportFormat::Json,
+        ExportFormat::Xlsx,
+        ExportFormat::Html,
+        ExportFormat::Markdown,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Tsv => "TSV",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Xlsx => "XLSX",
+            ExportFormat::Html => "HTML",
+            ExportFormat::Markdown => "Markdown",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Tsv => "tsv",
+            ExportFormat::Json => "json",
+            ExportFormat::Xlsx => "xlsx",
+            ExportFormat::Html => "html",
+            ExportFormat::Markdown => "md",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub(crate) enum ExportRows {
+    // Addresses present in every file (the table rows)
+    #[default]
+    Common,
+    // Addresses present in any file; missing cells get the empty marker
+    Union,
+}
+
+// Identity of a loaded file for the column choice: source path and column name
+pub(crate) type FileKey = (Option<PathBuf>, String);
+
+fn file_key(pf: &ParsedFile) -> FileKey {
+    (pf.path.clone(), pf.file_name.clone())
+}
+
+pub(crate) struct ExportOptions {
+    pub(crate) format: ExportFormat,
+    pub(crate) rows: ExportRows,
+    // Apply the filter bar expression to the exported rows
+    pub(crate) use_filter: bool,
+    pub(crate) diff_only: bool,
+    pub(crate) base: DisplayBase,
+    // Data columns as in the table (collapsed variants, expanded members) instead of the per-file choice
+    pub(crate) follow_table: bool,
+    // Files whose column is left out, by identity so the choice follows the file through
+    // removals, reorders and undo
+    pub(crate) excluded: BTreeSet<FileKey>,
+    pub(crate) diff_column: bool,
+    pub(crate) consensus_column: bool,
+    // Notes column: edited cells, failed rules, consensus without a majority
+    pub(crate) annotations: bool,
+    pub(crate) empty_marker: String,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            format: ExportFormat::default(),
+            rows: ExportRows::default(),
+            use_filter: true,
+            diff_only: false,
+            base: DisplayBase::Hex,
+            follow_table: true,
+            excluded: BTreeSet::new(),
+            diff_column: false,
+            consensus_column: false,
+            annotations: false,
+            empty_marker: "-".to_string(),
+        }
+    }
+}
+
+pub(crate) struct ExportRow {
+    pub(crate) addr: u64,
+    // One cell per header, extra columns included
+    pub(crate) cells: Vec<String>,
+    // Data cells that differ from the most common value in the row (always false for extra columns)
+    pub(crate) outliers: Vec<bool>,
+    // The exported data cells are not all equal (also true for a tie, which has no outliers)
+    pub(crate) differs: bool,
+}
+
+// Format-independent table: address column plus the exported columns in display order
+pub(crate) struct ExportTable {
+    pub(crate) base: DisplayBase,
+    pub(crate) empty_marker: String,
+    pub(crate) headers: Vec<String>,
+    pub(crate) rows: Vec<ExportRow>,
+}
+
+pub(crate) fn base_name(base: DisplayBase) -> &'static str {
+    match base {
+        DisplayBase::Hex => "hex",
+        DisplayBase::Bin => "bin",
+        DisplayBase::Dec => "dec",
+    }
+}
+
+// Address header of CSV / TSV exports; records the base and empty marker so Import reads the values back as written
+pub(crate) fn address_header(base: DisplayBase, empty_marker: &str) -> String {
+    format!("address (base={}; empty={})", base_name(base), empty_marker)
+}
+
+// Flag cells that differ from the row's most common value. A tie for the top count has no
+// outliers, like a Tie in the consensus and outlier views.
+fn outliers(cells: &[String]) -> Vec<bool> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for c in cells {
+        *counts.entry(c.as_str()).or_default() += 1;
+    }
+    let top = counts.values().copied().max().unwrap_or(0);
+    let mut leaders = counts.iter().filter(|&(_, &n)| n == top).map(|(&v, _)| v);
+    match (leaders.next(), leaders.next()) {
+        (Some(majority), None) => cells.iter().map(|c| c != majority).collect(),
+        _ => vec![false; cells.len()],
+    }
+}
+
+pub(crate) fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+impl ExportTable {
+    fn to_delimited(&self, csv: bool) -> String {
+        let field = |s: &str| if csv { csv_field(s) } else { s.replace(['\t', '\n', '\r'], " ") };
+        let sep = if csv { "," } else { "\t" };
+        let mut out = String::new();
+        let address = address_header(self.base, &self.empty_marker);
+        let header: Vec<String> = std::iter::once(address.as_str()).chain(self.headers.iter().map(String::as_str)).map(field).collect();
+        out.push_str(&header.join(sep));
+        out.push('\n');
+        for row in &self.rows {
+            let line: Vec<String> = std::iter::once(format_addr(row.addr)).chain(row.cells.iter().cloned()).map(|c| field(&c)).collect();
+            out.push_str(&line.join(sep));
+            out.push('\n');
+        }
+        out
+    }
+
+    fn to_json(&self) -> String {
+        let rows: Vec<serde_json::Value> = self
+            .rows
+            .iter()
+            .map(|row| {
+                serde_json::json!({
+                    "address": format_addr(row.addr),
+                    "values": row.cells,
+                    "differs": row.differs,
+                })
+            })
+            .collect();
+        let doc = serde_json::json!({ "base": base_name(self.base), "columns": self.headers, "rows": rows });
+        serde_json::to_string_pretty(&doc).unwrap_or_default()
+    }
+
+    fn to_xlsx(&self) -> anyhow::Result<Vec<u8>> {
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet();
+        sheet.set_name("Export")?;
+        let bold = Format::new().set_bold();
+        let diff_cell = Format::new().set_background_color(Color::RGB(0xFFC8C8));
+        let diff_addr = Format::new().set_background_color(Color::RGB(0xFFE4B5));
+        sheet.write_string_with_format(0, 0, "address", &bold)?;
+        for (col, name) in self.headers.iter().enumerate() {
+            sheet.write_string_with_format(0, col as u16 + 1, name, &bold)?;
+            sheet.set_column_width(col as u16 + 1, 16)?;
+        }
+        sheet.set_column_width(0, 12)?;
+        sheet.set_freeze_panes(1, 1)?;
+        for (r, row) in self.rows.iter().enumerate() {
+            let r = r as u32 + 1;
+            if row.differs {
+                sheet.write_string_with_format(r, 0, format_addr(row.addr), &diff_addr)?;
+            } else {
+                sheet.write_string(r, 0, format_addr(row.addr))?;
+            }
+            for (col, (cell, &outlier)) in row.cells.iter().zip(&row.outliers).enumerate() {
+                if outlier {
+                    sheet.write_string_with_format(r, col as u16 + 1, cell, &diff_cell)?;
+                } else {
+                    sheet.write_string(r, col as u16 + 1, cell)?;
+                }
+            }
+        }
+        Ok(workbook.save_to_buffer()?)
+    }
+
+    fn to_html(&self) -> String {
+        let mut out = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>SuffixCode Export</title>\n<style>\n\
+             body { font-family: sans-serif; }\n\
+             table { border-collapse: collapse; font-family: monospace; }\n\
+             th, td { border: 1px solid #ccc; padding: 2px 8px; }\n\
+             th { background: #eee; position: sticky; top: 0; }\n\
+             tr.diff td:first-child { background: #ffe4b5; }\n\
+             td.diff { background: #ffc8c8; }\n\
+             </style>\n</head>\n<body>\n",
+        );
+        out.push_str(&format!("<p>{} rows, {} columns, base {}</p>\n<table>\n<tr><th>address</th>", self.rows.len(), self.headers.len(), base_name(self.base)));
+        for name in &self.headers {
+            out.push_str(&format!("<th>{}</th>", html_escape(name)));
+        }
+        out.push_str("</tr>\n");
+        for row in &self.rows {
+            out.push_str(if row.differs { "<tr class=\"diff\">" } else { "<tr>" });
+            out.push_str(&format!("<td>{}</td>", format_addr(row.addr)));
+            for (cell, &outlier) in row.cells.iter().zip(&row.outliers) {
+                let class = if outlier { " class=\"diff\"" } else { "" };
+                out.push_str(&format!("<td{}>{}</td>", class, html_escape(cell)));
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</table>\n</body>\n</html>\n");
+        out
+    }
+
+    fn to_markdown(&self) -> String {
+        let cell = |s: &str| s.replace('|', "\\|").replace(['\n', '\r'], " ");
+        let mut out = String::from("| address |");
+        for name in &self.headers {
+            out.push_str(&format!(" {} |", cell(name)));
+        }
+        out.push_str("\n|---|");
+        out.push_str(&"---|".repeat(self.headers.len()));
+        out.push('\n');
+        for row in &self.rows {
+            out.push_str(&format!("| {} |", format_addr(row.addr)));
+            for (value, &outlier) in row.cells.iter().zip(&row.outliers) {
+                // Differing cells in bold
+                if outlier {
+                    out.push_str(&format!(" **{}** |", cell(value)));
+                } else {
+                    out.push_str(&format!(" {} |", cell(value)));
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    pub(crate) fn render(&self, format: ExportFormat) -> anyhow::Result<Vec<u8>> {
+        Ok(match format {
+            ExportFormat::Csv => self.to_delimited(true).into_bytes(),
+            ExportFormat::Tsv => self.to_delimited(false).into_bytes(),
+            ExportFormat::Json => self.to_json().into_bytes(),
+            ExportFormat::Xlsx => self.to_xlsx()?,
+            ExportFormat::Html => self.to_html().into_bytes(),
+            ExportFormat::Markdown => self.to_markdown().into_bytes(),
+        })
+    }
+}
+
+impl AppState {
+    // Exported data columns: (header, file index)
+    fn export_columns(&self, options: &ExportOptions) -> Vec<(String, usize)> {
+        let suffix = if self.value_view == derived::ValueView::Raw { String::new() } else { format!(" ({})", self.value_view.label()) };
+        if !options.follow_table {
+            return (0..self.files.len())
+                .filter(|&i| !options.excluded.contains(&file_key(&self.files[i])))
+                .map(|i| (format!("{}{}", self.files[i].file_name, suffix), i))
+                .collect();
+        }
+        self.table_columns()
+            .into_iter()
+            .map(|column| {
+                let name = match column.variant {
+                    Some((n, _)) if column.collapsed() => {
+                        let members: Vec<&str> = column.members.iter().map(|&i| self.files[i].file_name.as_str()).collect();
+                        format!("Variant {} ×{} ({})", n, members.len(), members.join(", "))
+                    }
+                    _ => self.files[column.file_idx].file_name.clone(),
+                };
+                (format!("{}{}", name, suffix), column.file_idx)
+            })
+            .collect()
+    }
+
+    fn export_addresses(&self, options: &ExportOptions) -> Vec<u64> {
+        let addrs: Vec<u64> = match options.rows {
+            ExportRows::Common if options.use_filter => return self.visible_addresses(),
+            ExportRows::Common => return self.intersect_addresses.clone(),
+            ExportRows::Union => self.files.iter().flat_map(|pf| pf.address_to_data.keys().copied()).collect::<BTreeSet<u64>>().into_iter().collect(),
+        };
+        match self.filter.as_ref().filter(|_| options.use_filter) {
+            Some(expr) => addrs.into_iter().filter(|&addr| expr.matches(&self.files, addr, self.display_base)).collect(),
+            None => addrs,
+        }
+    }
+
+    // Same rule as the table's Diff column; an address missing from some file also counts as a difference
+    fn row_differs(&self, addr: u64, base: DisplayBase) -> bool {
+        self.files.iter().any(|pf| !pf.address_to_data.contains_key(&addr)) || is_data_different(&self.files, addr, base)
+    }
+
+    fn row_notes(&self, addr: u64) -> String {
+        let mut notes = Vec::new();
+        let edited: Vec<&str> = self.files.iter().filter(|pf| pf.is_edited(addr)).map(|pf| pf.file_name.as_str()).collect();
+        if !edited.is_empty() {
+            notes.push(format!("edited: {}", edited.join(", ")));
+        }
+        if let Some(report) = &self.rule_report {
+            let failed: Vec<&str> = self.files.iter().enumerate().filter(|(i, _)| report.failed(*i, addr)).map(|(_, pf)| pf.file_name.as_str()).collect();
+            if !failed.is_empty() {
+                notes.push(format!("rule failed: {}", failed.join(", ")));
+            }
+        }
+        if let Some(entry) = self.consensus.as_ref().and_then(|c| c.entries.get(&addr))
+            && entry.status != ConsensusStatus::Majority
+        {
+            notes.push(format!("consensus: {} ({})", entry.status.label(), entry.candidates_text()));
+        }
+        notes.join("; ")
+    }
+
+    pub(crate) fn build_export_table(&self, options: &ExportOptions) -> ExportTable {
+        let columns = self.export_columns(options);
+        let baseline = self.files.get(self.baseline_file);
+        let consensus = self.consensus.as_ref().filter(|_| options.consensus_column);
+
+        let mut headers = Vec::new();
+        if options.diff_column {
+            headers.push("Diff".to_string());
+        }
+        if consensus.is_some() {
+            headers.push("Consensus".to_string());
+        }
+        headers.extend(columns.iter().map(|(name, _)| name.clone()));
+        if options.annotations {
+            headers.push("Notes".to_string());
+        }
+
+        let mut rows = Vec::new();
+        for addr in self.export_addresses(options) {
+            let differs = self.row_differs(addr, options.base);
+            if options.diff_only && !differs {
+                continue;
+            }
+            let data: Vec<String> = columns
+                .iter()
+                .map(|&(_, i)| {
+                    let pf = &self.files[i];
+                    if pf.address_to_data.contains_key(&addr) {
+                        derived::cell_value(pf, baseline, addr, self.value_view, options.base).0
+                    } else {
+                        options.empty_marker.clone()
+                    }
+                })
+                .collect();
+            let mut cells = Vec::new();
+            let mut flags = Vec::new();
+            if options.diff_column {
+                cells.push(if differs { "diff" } else { "same" }.to_string());
+                flags.push(false);
+            }
+            if let Some(consensus) = consensus {
+                cells.push(consensus.entries.get(&addr).map(|e| format!("{} ({:.0}%)", e.value, e.agreement())).unwrap_or_default());
+                flags.push(false);
+            }
+            let data_differs = data.iter().any(|c| c != &data[0]);
+            flags.extend(outliers(&data));
+            cells.extend(data);
+            if options.annotations {
+                cells.push(self.row_notes(addr));
+                flags.push(false);
+            }
+            rows.push(ExportRow { addr, cells, outliers: flags, differs: data_differs });
+        }
+        ExportTable { base: options.base, empty_marker: options.empty_marker.clone(), headers, rows }
+    }
+
+    // Options reproducing the main table as currently shown
+    fn match_table_view(&mut self) {
+        let options = &mut self.export_options;
+        options.rows = ExportRows::Common;
+        options.use_filter = true;
+        options.diff_only = false;
+        options.follow_table = true;
+        options.base = self.display_base;
+        options.diff_column = self.show_diff_column;
+        options.consensus_column = self.consensus.is_some();
+    }
+
+    pub(crate) fn show_export_window(&mut self, ctx: &egui::Context) {
+        let main_rect = ctx.input(|i| i.screen_rect());
+        let mut open = self.show_export;
+        egui::Window::new("Export")
+            .constrain_to(main_rect)
+            .max_size(main_rect.size())
+            .open(&mut open)
+            .show(ctx, |ui| self.export_ui(ui));
+        self.show_export = open;
+    }
+
+    fn export_ui(&mut self, ui: &mut egui::Ui) {
+        if self.files.is_empty() {
+            ui.label("No files loaded.");
+            return;
+        }
+        let options = &mut self.export_options;
+        ui.horizontal(|ui| {
+            ui.label("Format:");
+            for format in ExportFormat::ALL {
+                ui.selectable_value(&mut options.format, format, format.label());
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Rows:");
+            ui.selectable_value(&mut options.rows, ExportRows::Common, "Common");
+            ui.selectable_value(&mut options.rows, ExportRows::Union, "Union");
+            ui.checkbox(&mut options.use_filter, "Apply filter");
+            ui.checkbox(&mut options.diff_only, "Differing only");
+        });
+        if options.rows == ExportRows::Union {
+            ui.horizontal(|ui| {
+                ui.label("Empty marker:");
+                ui.add(egui::TextEdit::singleline(&mut options.empty_marker).desired_width(60.0));
+            });
+        }
+        ui.horizontal(|ui| {
+            ui.label("Base:");
+            ui.selectable_value(&mut options.base, DisplayBase::Hex, "HEX");
+            ui.selectable_value(&mut options.base, DisplayBase::Dec, "DEC");
+            ui.selectable_value(&mut options.base, DisplayBase::Bin, "BIN");
+        });
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("Extra columns:");
+            ui.checkbox(&mut options.diff_column, "Diff");
+            ui.add_enabled(self.consensus.is_some(), egui::Checkbox::new(&mut options.consensus_column, "Consensus"))
+                .on_disabled_hover_text("Build a consensus first (Analysis → Consensus)");
+            ui.checkbox(&mut options.annotations, "Notes");
+        });
+        ui.checkbox(&mut options.follow_table, "Data columns as in the table");
+        if !options.follow_table {
+            ui.horizontal(|ui| {
+                ui.label("Columns:");
+                if ui.small_button("All").clicked() {
+                    options.excluded.clear();
+                }
+                if ui.small_button("None").clicked() {
+                    options.excluded.extend(self.files.iter().map(file_key));
+                }
+            });
+            egui::ScrollArea::vertical().id_source("export_columns").max_height(200.0).show(ui, |ui| {
+                for pf in &self.files {
+                    let key = file_key(pf);
+                    let mut include = !options.excluded.contains(&key);
+                    if ui.checkbox(&mut include, &pf.file_name).changed() {
+                        if include {
+                            options.excluded.remove(&key);
+                        } else {
+                            options.excluded.insert(key);
+                        }
+                    }
+                }
+            });
+        }
+        if self.value_view != derived::ValueView::Raw {
+            ui.label(format!("Values are exported as {} against the baseline, as shown in the table.", self.value_view.label()));
+        }
+        ui.separator();
+        let selected = options.follow_table || self.files.iter().any(|pf| !options.excluded.contains(&file_key(pf)));
+        ui.horizontal(|ui| {
+            if ui.button("Match Table View").on_hover_text("Rows, columns and base exactly as the table shows them").clicked() {
+                self.match_table_view();
+            }
+            if ui.add_enabled(selected, egui::Button::new(format!("Export {}…", self.export_options.format.label()))).clicked() {
+                self.export_to_file();
+            }
+        });
+    }
+
+    fn export_to_file(&self) {
+        let options = &self.export_options;
+        let table = self.build_export_table(options);
+        if table.rows.is_empty() {
+            warn!("No data to export");
+            return;
+        }
+        let bytes = match table.render(options.format) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                error!("Export failed: {:?}", e);
+                return;
+            }
+        };
+        let name = format!("export.{}", options.format.extension());
+        if let Some(path) = rfd::FileDialog::new().set_file_name(&name).add_filter(options.format.label(), &[options.format.extension()]).save_file() {
+            if let Err(e) = fs::write(&path, bytes) {
+                error!("Export failed: {:?}", e);
+            } else {
+                info!("Exported: {}", path.to_string_lossy());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsed_file;
+
+    fn app_with(files: &[(&str, &[(u64, &str)])]) -> AppState {
+        let mut app = AppState::default();
+        for (name, rows) in files {
+            app.files.push(ParsedFile { file_name: name.to_string(), ..parsed_file(rows) });
+        }
+        app.recalc_intersection();
+        app
+    }
+
+    fn strings(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn outliers_flag_cells_against_a_single_leader() {
+        assert_eq!(outliers(&strings(&["7E", "7E", "80"])), [false, false, true]);
+        assert_eq!(outliers(&strings(&["80", "7E", "7E", "7E", "81"])), [true, false, false, false, true]);
+        assert_eq!(outliers(&strings(&["7E", "7E"])), [false, false]);
+        assert!(outliers(&[]).is_empty());
+    }
+
+    #[test]
+    fn outliers_flag_nothing_on_a_tie() {
+        assert_eq!(outliers(&strings(&["7E", "80", "7E", "80"])), [false; 4]);
+        assert_eq!(outliers(&strings(&["01", "02", "03"])), [false; 3]);
+    }
+
+    #[test]
+    fn column_choice_follows_files_after_removal_and_reorder() {
+        let mut app = app_with(&[("a", &[(0, "01")]), ("b", &[(0, "02")]), ("c", &[(0, "03")])]);
+        let mut options = ExportOptions { follow_table: false, use_filter: false, ..Default::default() };
+        options.excluded.insert(file_key(&app.files[1]));
+        assert_eq!(app.build_export_table(&options).headers, ["a", "c"]);
+
+        app.files.remove(0);
+        assert_eq!(app.build_export_table(&options).headers, ["c"]);
+        app.files.reverse();
+        app.recalc_intersection();
+        assert_eq!(app.build_export_table(&options).headers, ["c"]);
+    }
+
+    #[test]
+    fn union_rows_mark_missing_cells() {
+        let app = app_with(&[("a", &[(0, "01"), (1, "02")]), ("b", &[(1, "0x02"), (2, "03")])]);
+        let options = ExportOptions { rows: ExportRows::Union, follow_table: false, use_filter: false, diff_column: true, empty_marker: "-".to_string(), ..Default::default() };
+        let table = app.build_export_table(&options);
+        assert_eq!(table.headers, ["Diff", "a", "b"]);
+        let rows: Vec<(u64, Vec<String>, bool)> = table.rows.iter().map(|r| (r.addr, r.cells.clone(), r.differs)).collect();
+        assert_eq!(
+            rows,
+            [
+                (0, strings(&["diff", "0x01", "-"]), true),
+                (1, strings(&["same", "0x02", "0x02"]), false),
+                (2, strings(&["diff", "-", "0x03"]), true)
+            ]
+        );
+
+        let diff_only = app.build_export_table(&ExportOptions { diff_only: true, ..options });
+        assert_eq!(diff_only.rows.iter().map(|r| r.addr).collect::<Vec<_>>(), [0, 2]);
+        let common = app.build_export_table(&ExportOptions { follow_table: false, use_filter: false, ..Default::default() });
+        assert_eq!(common.rows.iter().map(|r| r.addr).collect::<Vec<_>>(), [1]);
+    }
+}
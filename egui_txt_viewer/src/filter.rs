@@ -0,0 +1,413 @@
+use crate::{AppState, DisplayBase, ParsedFile, group_files_by_value, is_data_different, parse_data_value, parse_user_number};
+
+// Row filter expressions, e.g.
+//   addr >= 0x100 && addr < 0x200 && distinct > 1
+//   diff || value[1] & 0x80 != 0
+//   !(value["golden"] == 0x7E)
+// Fields: addr, distinct (number of different displayed values), diff (true when distinct > 1),
+//   value (any file), value[N] (N-th file column, 1-based), value["text"] (files whose name contains text)
+// Operators: == != < <= > >=, optional '& mask' on the left side, && || ! (or and / or / not), parentheses
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CmpOp {
+    fn apply(self, a: u64, b: u64) -> bool {
+        match self {
+            CmpOp::Eq => a == b,
+            CmpOp::Ne => a != b,
+            CmpOp::Lt => a < b,
+            CmpOp::Le => a <= b,
+            CmpOp::Gt => a > b,
+            CmpOp::Ge => a >= b,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum FileSel {
+    Any,
+    // 0-based file index
+    Index(usize),
+    // Case-insensitive substring of the file name
+    Name(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Field {
+    Addr,
+    Distinct,
+    Value(FileSel),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Diff,
+    Compare { field: Field, mask: Option<u64>, op: CmpOp, rhs: u64 },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Str(String),
+    Op(CmpOp),
+    Amp,
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            ' ' | '\t' | '\n' | '\r' => i += 1,
+            '(' => { tokens.push(Token::LParen); i += 1; }
+            ')' => { tokens.push(Token::RParen); i += 1; }
+            '[' => { tokens.push(Token::LBracket); i += 1; }
+            ']' => { tokens.push(Token::RBracket); i += 1; }
+            '&' if next == Some('&') => { tokens.push(Token::And); i += 2; }
+            '|' if next == Some('|') => { tokens.push(Token::Or); i += 2; }
+            '&' => { tokens.push(Token::Amp); i += 1; }
+            '=' if next == Some('=') => { tokens.push(Token::Op(CmpOp::Eq)); i += 2; }
+            '!' if next == Some('=') => { tokens.push(Token::Op(CmpOp::Ne)); i += 2; }
+            '<' if next == Some('=') => { tokens.push(Token::Op(CmpOp::Le)); i += 2; }
+            '>' if next == Some('=') => { tokens.push(Token::Op(CmpOp::Ge)); i += 2; }
+            '<' => { tokens.push(Token::Op(CmpOp::Lt)); i += 1; }
+            '>' => { tokens.push(Token::Op(CmpOp::Gt)); i += 1; }
+            '!' => { tokens.push(Token::Not); i += 1; }
+            '"' => {
+                let start = i + 1;
+                let Some(len) = chars[start..].iter().position(|&c| c == '"') else {
+                    return Err("unterminated string".to_string());
+                };
+                tokens.push(Token::Str(chars[start..start + len].iter().collect()));
+                i = start + len + 1;
+            }
+            c if c.is_ascii_alphanumeric() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                match word.to_ascii_lowercase().as_str() {
+                    "and" => tokens.push(Token::And),
+                    "or" => tokens.push(Token::Or),
+                    "not" => tokens.push(Token::Not),
+                    _ => tokens.push(Token::Word(word)),
+                }
+            }
+            other => return Err(format!("unexpected character '{}'", other)),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), String> {
+        match self.next() {
+            Some(t) if t == token => Ok(()),
+            other => Err(format!("expected {:?}, found {:?}", token, other)),
+        }
+    }
+
+    fn number(&mut self, what: &str) -> Result<u64, String> {
+        match self.next() {
+            Some(Token::Word(w)) => parse_user_number(&w).ok_or_else(|| format!("invalid number '{}'", w)),
+            other => Err(format!("expected {}, found {:?}", what, other)),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            lhs = Expr::Or(Box::new(lhs), Box::new(self.and()?));
+        }
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.next();
+            lhs = Expr::And(Box::new(lhs), Box::new(self.unary()?));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        match self.peek() {
+            Some(Token::Not) => {
+                self.next();
+                Ok(Expr::Not(Box::new(self.unary()?)))
+            }
+            Some(Token::LParen) => {
+                self.next();
+                let inner = self.or()?;
+                self.expect(Token::RParen)?;
+                Ok(inner)
+            }
+            _ => self.comparison(),
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let field = match self.next() {
+            Some(Token::Word(w)) => match w.to_ascii_lowercase().as_str() {
+                "diff" => return Ok(Expr::Diff),
+                "addr" | "address" => Field::Addr,
+                "distinct" => Field::Distinct,
+                "value" | "val" => {
+                    if self.peek() == Some(&Token::LBracket) {
+                        self.next();
+                        let sel = match self.next() {
+                            Some(Token::Str(name)) => FileSel::Name(name.to_lowercase()),
+                            Some(Token::Word(w)) => match w.parse::<usize>() {
+                                Ok(n) if n >= 1 => FileSel::Index(n - 1),
+                                _ => return Err(format!("invalid file column '{}' (1-based)", w)),
+                            },
+                            other => return Err(format!("expected file column or name, found {:?}", other)),
+                        };
+                        self.expect(Token::RBracket)?;
+                        Field::Value(sel)
+                    } else {
+                        Field::Value(FileSel::Any)
+                    }
+                }
+                _ => return Err(format!("unknown field '{}'", w)),
+            },
+            other => return Err(format!("expected field, found {:?}", other)),
+        };
+        let mask = if self.peek() == Some(&Token::Amp) {
+            self.next();
+            Some(self.number("mask")?)
+        } else {
+            None
+        };
+        let op = match self.next() {
+            Some(Token::Op(op)) => op,
+            other => return Err(format!("expected comparison operator, found {:?}", other)),
+        };
+        let rhs = self.number("number")?;
+        Ok(Expr::Compare { field, mask, op, rhs })
+    }
+}
+
+pub(crate) fn parse_filter(text: &str) -> Result<Expr, String> {
+    let mut parser = Parser { tokens: tokenize(text)?, pos: 0 };
+    let expr = parser.or()?;
+    if let Some(extra) = parser.peek() {
+        return Err(format!("unexpected {:?}", extra));
+    }
+    Ok(expr)
+}
+
+impl Expr {
+    pub(crate) fn matches(&self, files: &[ParsedFile], addr: u64, display_base: DisplayBase) -> bool {
+        match self {
+            Expr::And(a, b) => a.matches(files, addr, display_base) && b.matches(files, addr, display_base),
+            Expr::Or(a, b) => a.matches(files, addr, display_base) || b.matches(files, addr, display_base),
+            Expr::Not(e) => !e.matches(files, addr, display_base),
+            Expr::Diff => is_data_different(files, addr, display_base),
+            Expr::Compare { field, mask, op, rhs } => {
+                let test = |v: u64| op.apply(mask.map_or(v, |m| v & m), *rhs);
+                match field {
+                    Field::Addr => test(addr),
+                    Field::Distinct => test(group_files_by_value(files, addr, display_base).len() as u64),
+                    Field::Value(sel) => files
+                        .iter()
+                        .enumerate()
+                        .filter(|(idx, pf)| match sel {
+                            FileSel::Any => true,
+                            FileSel::Index(i) => idx == i,
+                            FileSel::Name(name) => pf.file_name.to_lowercase().contains(name.as_str()),
+                        })
+                        .filter_map(|(_, pf)| pf.address_to_data.get(&addr).and_then(|raw| parse_data_value(raw)))
+                        .any(test),
+                }
+            }
+        }
+    }
+}
+
+impl AppState {
+    // Compile the filter bar text; an empty text clears the filter
+    pub(crate) fn apply_filter(&mut self) {
+        let text = self.filter_text.trim();
+        if text.is_empty() {
+            self.filter = None;
+            self.filter_error = None;
+        } else {
+            match parse_filter(text) {
+                Ok(expr) => {
+                    self.filter = Some(expr);
+                    self.filter_error = None;
+                }
+                Err(e) => {
+                    self.filter_error = Some(e);
+                    return;
+                }
+            }
+        }
+        self.filter_revision += 1;
+    }
+
+    // Recompute visible_rows when the data, the filter or the display base changed
+    pub(crate) fn refresh_visible_rows(&mut self) {
+        let key = (self.data_revision, self.filter_revision, self.display_base);
+        if self.visible_for == Some(key) {
+            return;
+        }
+        self.visible_for = Some(key);
+        self.visible_rows = match &self.filter {
+            Some(expr) => (0..self.intersect_addresses.len())
+                .filter(|&i| expr.matches(&self.files, self.intersect_addresses[i], self.display_base))
+                .collect(),
+            None => (0..self.intersect_addresses.len()).collect(),
+        };
+    }
+
+    // Addresses of the rows left by the filter
+    pub(crate) fn visible_addresses(&self) -> Vec<u64> {
+        self.visible_rows.iter().map(|&i| self.intersect_addresses[i]).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsed_file;
+
+    fn cmp(field: Field, op: CmpOp, rhs: u64) -> Expr {
+        Expr::Compare { field, mask: None, op, rhs }
+    }
+
+    fn file(name: &str, values: &[(u64, &str)]) -> ParsedFile {
+        ParsedFile { file_name: name.to_string(), ..parsed_file(values) }
+    }
+
+    fn matches(text: &str, files: &[ParsedFile], addr: u64) -> bool {
+        parse_filter(text).unwrap().matches(files, addr, DisplayBase::Hex)
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let a = cmp(Field::Addr, CmpOp::Eq, 1);
+        let b = cmp(Field::Addr, CmpOp::Eq, 2);
+        let c = cmp(Field::Addr, CmpOp::Eq, 3);
+        let expected = Expr::Or(Box::new(a.clone()), Box::new(Expr::And(Box::new(b.clone()), Box::new(c.clone()))));
+        assert_eq!(parse_filter("addr == 1 || addr == 2 && addr == 3"), Ok(expected.clone()));
+        assert_eq!(parse_filter("addr == 1 or addr == 2 and addr == 3"), Ok(expected));
+        // Chains associate to the left
+        let left = Expr::Or(Box::new(Expr::Or(Box::new(a.clone()), Box::new(b.clone()))), Box::new(c.clone()));
+        assert_eq!(parse_filter("addr == 1 || addr == 2 || addr == 3"), Ok(left));
+        // ! applies to the nearest operand only
+        let not = Expr::And(Box::new(Expr::Not(Box::new(a))), Box::new(b));
+        assert_eq!(parse_filter("!addr == 1 && addr == 2"), Ok(not));
+    }
+
+    #[test]
+    fn parentheses_override_precedence() {
+        let a = cmp(Field::Addr, CmpOp::Eq, 1);
+        let b = cmp(Field::Addr, CmpOp::Eq, 2);
+        let c = cmp(Field::Addr, CmpOp::Eq, 3);
+        let expected = Expr::And(Box::new(Expr::Or(Box::new(a.clone()), Box::new(b.clone()))), Box::new(c));
+        assert_eq!(parse_filter("(addr == 1 || addr == 2) && addr == 3"), Ok(expected));
+        let not = Expr::Not(Box::new(Expr::And(Box::new(a), Box::new(b))));
+        assert_eq!(parse_filter("not ((addr == 1) and addr == 2)"), Ok(not));
+    }
+
+    #[test]
+    fn parses_fields_operators_and_masks() {
+        for (text, op) in [("==", CmpOp::Eq), ("!=", CmpOp::Ne), ("<", CmpOp::Lt), ("<=", CmpOp::Le), (">", CmpOp::Gt), (">=", CmpOp::Ge)] {
+            assert_eq!(parse_filter(&format!("addr {} 0x10", text)), Ok(cmp(Field::Addr, op, 0x10)), "{}", text);
+        }
+        assert_eq!(parse_filter("diff"), Ok(Expr::Diff));
+        assert_eq!(parse_filter("DISTINCT > 1"), Ok(cmp(Field::Distinct, CmpOp::Gt, 1)));
+        assert_eq!(parse_filter("address == 10h"), Ok(cmp(Field::Addr, CmpOp::Eq, 0x10)));
+        assert_eq!(parse_filter("value == 0b101"), Ok(cmp(Field::Value(FileSel::Any), CmpOp::Eq, 5)));
+        assert_eq!(parse_filter("val[2] == 7"), Ok(cmp(Field::Value(FileSel::Index(1)), CmpOp::Eq, 7)));
+        assert_eq!(parse_filter("value[\"Golden\"] == 1"), Ok(cmp(Field::Value(FileSel::Name("golden".to_string())), CmpOp::Eq, 1)));
+        assert_eq!(
+            parse_filter("value[1] & 0x80 != 0"),
+            Ok(Expr::Compare { field: Field::Value(FileSel::Index(0)), mask: Some(0x80), op: CmpOp::Ne, rhs: 0 })
+        );
+    }
+
+    #[test]
+    fn comparisons_apply_each_operator() {
+        let cases = [(CmpOp::Eq, [false, true, false]), (CmpOp::Ne, [true, false, true]), (CmpOp::Lt, [true, false, false]), (CmpOp::Le, [true, true, false]), (CmpOp::Gt, [false, false, true]), (CmpOp::Ge, [false, true, true])];
+        for (op, expected) in cases {
+            let got: Vec<bool> = [4, 5, 6].iter().map(|&a| op.apply(a, 5)).collect();
+            assert_eq!(got, expected, "{:?}", op);
+        }
+    }
+
+    #[test]
+    fn matches_values_per_file() {
+        let files = [file("golden.txt", &[(0x10, "7E"), (0x11, "81")]), file("dut.txt", &[(0x10, "7F"), (0x11, "81")])];
+        assert!(matches("value == 0x7F", &files, 0x10));
+        assert!(!matches("value[1] == 0x7F", &files, 0x10));
+        assert!(matches("value[\"dut\"] == 0x7F", &files, 0x10));
+        assert!(!matches("value[3] == 0x7F", &files, 0x10));
+        assert!(matches("value[1] & 0x80 != 0", &files, 0x11));
+        assert!(matches("diff && distinct == 2", &files, 0x10));
+        assert!(!matches("diff", &files, 0x11));
+        assert!(matches("addr >= 0x10 && addr < 0x11 && !(value[\"golden\"] == 0x7F)", &files, 0x10));
+        assert!(!matches("addr >= 0x10 && addr < 0x11", &files, 0x11));
+    }
+
+    #[test]
+    fn reports_malformed_input() {
+        let err = |text: &str| parse_filter(text).unwrap_err();
+        assert_eq!(err("addr == 0x1G"), "invalid number '0x1G'");
+        assert_eq!(err("addr = 1"), "unexpected character '='");
+        assert_eq!(err("addr == 1 $"), "unexpected character '$'");
+        assert_eq!(err("value[\"dut == 1"), "unterminated string");
+        assert_eq!(err("bogus == 1"), "unknown field 'bogus'");
+        assert_eq!(err("value[0] == 1"), "invalid file column '0' (1-based)");
+        assert_eq!(err("value[] == 1"), "expected file column or name, found Some(RBracket)");
+        assert_eq!(err("value[1 == 1"), "expected RBracket, found Some(Op(Eq))");
+        assert_eq!(err("addr 1"), "expected comparison operator, found Some(Word(\"1\"))");
+        assert_eq!(err("addr =="), "expected number, found None");
+        assert_eq!(err("addr & == 1"), "expected mask, found Some(Op(Eq))");
+        assert_eq!(err("(addr == 1"), "expected RParen, found None");
+        assert_eq!(err("addr == 1)"), "unexpected RParen");
+        assert_eq!(err("addr == 1 &&"), "expected field, found None");
+        assert_eq!(err(""), "expected field, found None");
+    }
+}
@@ -0,0 +1,268 @@
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{AppState, DisplayBase, ParsedFile, format_addr, format_data_with_base, generate_palette};
+
+pub(crate) struct SeparationRow {
+    pub(crate) addr: u64,
+    // Total variation distance between the two value distributions:
+    // 0 = identical distributions, 1 = the value alone tells the groups apart
+    pub(crate) score: f32,
+    pub(crate) dist_a: BTreeMap<String, usize>,
+    pub(crate) dist_b: BTreeMap<String, usize>,
+}
+
+pub(crate) struct GroupComparison {
+    revision: u64,
+    display_base: DisplayBase,
+    // Group tags of all files at compute time, so retagging invalidates the cache
+    tags: Vec<String>,
+    group_a: String,
+    group_b: String,
+    pub(crate) n_a: usize,
+    pub(crate) n_b: usize,
+    // Sorted by score desc, then address asc
+    pub(crate) rows: Vec<SeparationRow>,
+}
+
+fn distribution(files: &[&ParsedFile], addr: u64, display_base: DisplayBase) -> BTreeMap<String, usize> {
+    let mut dist: BTreeMap<String, usize> = BTreeMap::new();
+    for pf in files {
+        let raw = pf.address_to_data.get(&addr).cloned().unwrap_or_default();
+        *dist.entry(format_data_with_base(&raw, display_base)).or_default() += 1;
+    }
+    dist
+}
+
+fn separation_score(a: &BTreeMap<String, usize>, n_a: usize, b: &BTreeMap<String, usize>, n_b: usize) -> f32 {
+    if n_a == 0 || n_b == 0 {
+        return 0.0;
+    }
+    let values: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+    let diff: f32 = values
+        .into_iter()
+        .map(|v| {
+            let pa = a.get(v).copied().unwrap_or(0) as f32 / n_a as f32;
+            let pb = b.get(v).copied().unwrap_or(0) as f32 / n_b as f32;
+            (pa - pb).abs()
+        })
+        .sum();
+    diff * 0.5
+}
+
+// Top values of a distribution as "value xN" text
+fn distribution_text(dist: &BTreeMap<String, usize>, limit: usize) -> String {
+    let mut entries: Vec<(&String, &usize)> = dist.iter().collect();
+    entries.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    let mut text = entries
+        .iter()
+        .take(limit)
+        .map(|(v, c)| format!("{} x{}", v, c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if entries.len() > limit {
+        text.push_str(", …");
+    }
+    text
+}
+
+impl GroupComparison {
+    fn compute(files: &[ParsedFile], addrs: &[u64], group_a: &str, group_b: &str, display_base: DisplayBase, revision: u64) -> Self {
+        let members_a: Vec<&ParsedFile> = files.iter().filter(|pf| pf.group == group_a).collect();
+        let members_b: Vec<&ParsedFile> = files.iter().filter(|pf| pf.group == group_b).collect();
+        let (n_a, n_b) = (members_a.len(), members_b.len());
+        let mut rows: Vec<SeparationRow> = addrs
+            .iter()
+            .map(|&addr| {
+                let dist_a = distribution(&members_a, addr, display_base);
+                let dist_b = distribution(&members_b, addr, display_base);
+                let score = separation_score(&dist_a, n_a, &dist_b, n_b);
+                SeparationRow { addr, score, dist_a, dist_b }
+            })
+            .collect();
+        rows.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.addr.cmp(&b.addr)));
+        Self {
+            revision,
+            display_base,
+            tags: files.iter().map(|pf| pf.group.clone()).collect(),
+            group_a: group_a.to_string(),
+            group_b: group_b.to_string(),
+            n_a,
+            n_b,
+            rows,
+        }
+    }
+
+    fn is_stale(&self, state: &AppState) -> bool {
+        self.revision != state.data_revision
+            || self.display_base != state.display_base
+            || self.group_a != state.group_a
+            || self.group_b != state.group_b
+            || self.tags.len() != state.files.len()
+            || self.tags.iter().zip(&state.files).any(|(t, pf)| *t != pf.group)
+    }
+}
+
+impl AppState {
+    // Distinct non-empty group tags in name order
+    fn group_names(&self) -> Vec<String> {
+        let names: BTreeSet<&String> = self.files.iter().map(|pf| &pf.group).filter(|g| !g.is_empty()).collect();
+        names.into_iter().cloned().collect()
+    }
+
+    pub(crate) fn show_groups_window(&mut self, ctx: &egui::Context) {
+        let main_rect = ctx.input(|i| i.screen_rect());
+        let mut open = self.show_groups;
+        egui::Window::new("Groups")
+            .constrain_to(main_rect)
+            .max_size(main_rect.size())
+            .open(&mut open)
+            .show(ctx, |ui| self.groups_ui(ui));
+        self.show_groups = open;
+    }
+
+    fn groups_ui(&mut self, ui: &mut egui::Ui) {
+        if self.files.is_empty() {
+            ui.label("No files loaded.");
+            return;
+        }
+        ui.label("Tag files:");
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.group_tag_input).hint_text("Group name").desired_width(100.0));
+            let name = self.group_tag_input.trim().to_string();
+            if ui.add_enabled(!name.is_empty(), egui::Button::new("Tag Untagged")).clicked() {
+                let before = self.file_layouts();
+                for pf in self.files.iter_mut().filter(|pf| pf.group.is_empty()) {
+                    pf.group = name.clone();
+                }
+                self.record_layouts("group tagging", before);
+            }
+            if ui.button("Clear Tags").clicked() {
+                let before = self.file_layouts();
+                for pf in &mut self.files {
+                    pf.group.clear();
+                }
+                self.record_layouts("clear group tags", before);
+            }
+        });
+        let snapshot = self.file_layouts();
+        let (mut focused, mut released) = (false, false);
+        egui::ScrollArea::vertical().id_source("group_tags").max_height(160.0).show(ui, |ui| {
+            egui::Grid::new("group_tag_grid").striped(true).show(ui, |ui| {
+                for pf in &mut self.files {
+                    ui.label(&pf.file_name);
+                    let resp = ui.add(egui::TextEdit::singleline(&mut pf.group).desired_width(100.0));
+                    focused |= resp.gained_focus();
+                    released |= resp.lost_focus();
+                    ui.end_row();
+                }
+            });
+        });
+        // Typing into a name field becomes one undo step when the field loses focus
+        if released && let Some(before) = self.group_edit_before.take() {
+            self.record_layouts("group tag edit", before);
+        }
+        if focused {
+            self.group_edit_before = Some(snapshot);
+        }
+
+        ui.separator();
+        let names = self.group_names();
+        ui.horizontal(|ui| {
+            ui.label("Compare");
+            egui::ComboBox::from_id_source("group_a").selected_text(&self.group_a).show_ui(ui, |ui| {
+                for name in &names {
+                    ui.selectable_value(&mut self.group_a, name.clone(), name);
+                }
+            });
+            ui.label("vs");
+            egui::ComboBox::from_id_source("group_b").selected_text(&self.group_b).show_ui(ui, |ui| {
+                for name in &names {
+                    ui.selectable_value(&mut self.group_b, name.clone(), name);
+                }
+            });
+        });
+        if self.group_a.is_empty() || self.group_b.is_empty() || self.group_a == self.group_b {
+            ui.label("Pick two different groups to compare.");
+            return;
+        }
+        if self.group_comparison.as_ref().is_none_or(|c| c.is_stale(self)) {
+            self.group_comparison = Some(GroupComparison::compute(
+                &self.files,
+                &self.intersect_addresses,
+                &self.group_a,
+                &self.group_b,
+                self.display_base,
+                self.data_revision,
+            ));
+        }
+        let Some(cmp) = &self.group_comparison else { return };
+        ui.label(format!(
+            "{}: {} files  |  {}: {} files  |  Score = total variation distance (1.0 = fully separating)",
+            cmp.group_a, cmp.n_a, cmp.group_b, cmp.n_b
+        ));
+
+        let mut select = None;
+        ui.push_id("group_ranking", |ui| {
+            TableBuilder::new(ui)
+                .striped(true)
+                .max_scroll_height(260.0)
+                .column(Column::initial(110.0).resizable(true))
+                .column(Column::initial(70.0).resizable(true))
+                .column(Column::initial(200.0).resizable(true))
+                .column(Column::remainder())
+                .header(24.0, |mut header| {
+                    header.col(|ui| { ui.label("Address"); });
+                    header.col(|ui| { ui.label("Score"); });
+                    header.col(|ui| { ui.label(&cmp.group_a); });
+                    header.col(|ui| { ui.label(&cmp.group_b); });
+                })
+                .body(|body| {
+                    body.rows(20.0, cmp.rows.len(), |mut row| {
+                        let r = &cmp.rows[row.index()];
+                        row.col(|ui| {
+                            if ui.selectable_label(self.group_selected == Some(r.addr), format_addr(r.addr)).clicked() {
+                                select = Some(r.addr);
+                            }
+                        });
+                        row.col(|ui| { ui.label(format!("{:.3}", r.score)); });
+                        row.col(|ui| { ui.monospace(distribution_text(&r.dist_a, 3)); });
+                        row.col(|ui| { ui.monospace(distribution_text(&r.dist_b, 3)); });
+                    });
+                });
+        });
+
+        if let Some(row) = self.group_selected.and_then(|addr| cmp.rows.iter().find(|r| r.addr == addr)) {
+            ui.separator();
+            ui.label(format!("Value distribution at {} (score {:.3})", format_addr(row.addr), row.score));
+            let values: BTreeSet<&String> = row.dist_a.keys().chain(row.dist_b.keys()).collect();
+            let colors = generate_palette(2);
+            egui::Grid::new("group_distribution").striped(true).show(ui, |ui| {
+                ui.label("Value");
+                ui.label(&cmp.group_a);
+                ui.label(&cmp.group_b);
+                ui.end_row();
+                for v in values {
+                    ui.monospace(v);
+                    for (dist, n, color) in [(&row.dist_a, cmp.n_a, colors[0]), (&row.dist_b, cmp.n_b, colors[1])] {
+                        let count = dist.get(v).copied().unwrap_or(0);
+                        let frac = if n == 0 { 0.0 } else { count as f32 / n as f32 };
+                        ui.horizontal(|ui| {
+                            let (rect, _) = ui.allocate_exact_size(egui::vec2(100.0, 12.0), egui::Sense::hover());
+                            ui.painter().rect_filled(rect, 0.0, egui::Color32::from_gray(230));
+                            let bar = egui::Rect::from_min_size(rect.min, egui::vec2(rect.width() * frac, rect.height()));
+                            ui.painter().rect_filled(bar, 0.0, color);
+                            ui.label(format!("{} ({:.1}%)", count, frac * 100.0));
+                        });
+                    }
+                    ui.end_row();
+                }
+            });
+        }
+        if let Some(addr) = select {
+            self.group_selected = Some(addr);
+            self.select_addr(addr);
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use eframe::egui;
+use std::collections::BTreeSet;
+
+use crate::{AppState, ParsedFile, format_addr, parse_data_value};
+
+const BYTES_PER_LINE: u64 = 16;
+
+// Side-by-side hex dump of two files, one 16-byte line per row
+pub(crate) struct HexDiff {
+    pub(crate) a: usize,
+    pub(crate) b: usize,
+    diff_only: bool,
+    // (data revision, a, b, diff_only) the lines were built for
+    built_for: Option<(u64, usize, usize, bool)>,
+    // Start address of each shown line
+    lines: Vec<u64>,
+    diff_bytes: usize,
+}
+
+impl HexDiff {
+    pub(crate) fn new(a: usize, b: usize) -> Self {
+        Self { a, b, diff_only: false, built_for: None, lines: Vec::new(), diff_bytes: 0 }
+    }
+
+    fn rebuild(&mut self, files: &[ParsedFile], revision: u64) {
+        let (fa, fb) = (&files[self.a], &files[self.b]);
+        let bases: BTreeSet<u64> = fa
+            .address_to_data
+            .keys()
+            .chain(fb.address_to_data.keys())
+            .map(|addr| addr - addr % BYTES_PER_LINE)
+            .collect();
+        self.diff_bytes = 0;
+        self.lines.clear();
+        for base in bases {
+            let differing = (base..base.saturating_add(BYTES_PER_LINE)).filter(|&addr| byte_at(fa, addr) != byte_at(fb, addr)).count();
+            self.diff_bytes += differing;
+            if !self.diff_only || differing > 0 {
+                self.lines.push(base);
+            }
+        }
+        self.built_for = Some((revision, self.a, self.b, self.diff_only));
+    }
+}
+
+fn byte_at(pf: &ParsedFile, addr: u64) -> Option<u8> {
+    pf.address_to_data.get(&addr).and_then(|raw| parse_data_value(raw)).map(|v| (v & 0xFF) as u8)
+}
+
+fn ascii(byte: u8) -> char {
+    if (0x20..0x7F).contains(&byte) { byte as char } else { '.' }
+}
+
+impl AppState {
+    // Open the hex diff for a file column; the partner defaults to the next file
+    pub(crate) fn open_hexdiff(&mut self, file_idx: usize) {
+        if self.files.len() < 2 {
+            return;
+        }
+        self.hexdiff = Some(HexDiff::new(file_idx, (file_idx + 1) % self.files.len()));
+    }
+
+    pub(crate) fn show_hexdiff_window(&mut self, ctx: &egui::Context) {
+        let Some(view) = &self.hexdiff else { return };
+        if view.a >= self.files.len() || view.b >= self.files.len() {
+            self.hexdiff = None;
+            return;
+        }
+        let main_rect = ctx.input(|i| i.screen_rect());
+        let mut open = true;
+        egui::Window::new("Hex Diff")
+            .constrain_to(main_rect)
+            .max_size(main_rect.size())
+            .open(&mut open)
+            .show(ctx, |ui| self.hexdiff_ui(ui));
+        if !open {
+            self.hexdiff = None;
+        }
+    }
+
+    fn hexdiff_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(view) = &mut self.hexdiff else { return };
+        ui.horizontal(|ui| {
+            for (label, idx, id) in [("A:", &mut view.a, "hexdiff_a"), ("B:", &mut view.b, "hexdiff_b")] {
+                ui.label(label);
+                egui::ComboBox::from_id_source(id).selected_text(self.files[*idx].file_name.as_str()).show_ui(ui, |ui| {
+                    for (i, pf) in self.files.iter().enumerate() {
+                        ui.selectable_value(idx, i, &pf.file_name);
+                    }
+                });
+            }
+            ui.checkbox(&mut view.diff_only, "Only lines with differences");
+        });
+        if view.built_for != Some((self.data_revision, view.a, view.b, view.diff_only)) {
+            view.rebuild(&self.files, self.data_revision);
+        }
+        ui.label(format!("{} differing bytes, {} lines shown. Both sides scroll together.", view.diff_bytes, view.lines.len()));
+        ui.separator();
+
+        let (fa, fb) = (&self.files[view.a], &self.files[view.b]);
+        let font = egui::FontId::monospace(12.0);
+        let char_w = ui.fonts(|f| f.glyph_width(&font, '0'));
+        let row_h = ui.fonts(|f| f.row_height(&font)) + 2.0;
+        // Columns, in characters: address, hex bytes, ascii gutter, per side
+        let addr_chars = 10.0;
+        let hex_chars = BYTES_PER_LINE as f32 * 3.0 + 1.0;
+        let side_chars = hex_chars + BYTES_PER_LINE as f32 + 3.0;
+        let width = (addr_chars + side_chars * 2.0) * char_w;
+        let diff_bg = egui::Color32::from_rgb(255, 200, 200);
+        let text_color = ui.visuals().text_color();
+        let missing_color = egui::Color32::GRAY;
+
+        egui::ScrollArea::both().auto_shrink([false; 2]).max_height(520.0).show_rows(ui, row_h, view.lines.len(), |ui, range| {
+            for &base in &view.lines[range] {
+                let (rect, resp) = ui.allocate_exact_size(egui::vec2(width, row_h), egui::Sense::hover());
+                let painter = ui.painter();
+                let y = rect.center().y;
+                let at = |chars: f32| rect.left() + chars * char_w;
+                painter.text(egui::pos2(at(0.0), y), egui::Align2::LEFT_CENTER, format_addr(base), font.clone(), text_color);
+                let mut hovered = None;
+                for (side, (pf, other)) in [(fa, fb), (fb, fa)].into_iter().enumerate() {
+                    let hex_left = addr_chars + side as f32 * side_chars;
+                    let ascii_left = hex_left + hex_chars + 1.0;
+                    for i in 0..BYTES_PER_LINE {
+                        let addr = base + i;
+                        let byte = byte_at(pf, addr);
+                        // Extra gap between the two 8-byte halves
+                        let col = hex_left + i as f32 * 3.0 + if i >= 8 { 1.0 } else { 0.0 };
+                        let cell = egui::Rect::from_min_size(egui::pos2(at(col), rect.top()), egui::vec2(char_w * 2.0, row_h));
+                        let ascii_cell = egui::Rect::from_min_size(egui::pos2(at(ascii_left + i as f32), rect.top()), egui::vec2(char_w, row_h));
+                        if byte != byte_at(other, addr) {
+                            painter.rect_filled(cell, 0.0, diff_bg);
+                            painter.rect_filled(ascii_cell, 0.0, diff_bg);
+                        }
+                        let (hex, ch, color) = match byte {
+                            Some(b) => (format!("{:02X}", b), ascii(b), text_color),
+                            None => ("--".to_string(), ' ', missing_color),
+                        };
+                        painter.text(cell.left_center(), egui::Align2::LEFT_CENTER, hex, font.clone(), color);
+                        painter.text(ascii_cell.left_center(), egui::Align2::LEFT_CENTER, ch, font.clone(), color);
+                        if resp.hover_pos().is_some_and(|p| cell.contains(p) || ascii_cell.contains(p)) {
+                            hovered = Some(addr);
+                        }
+                    }
+                }
+                if let Some(addr) = hovered {
+                    let show = |b: Option<u8>| b.map_or_else(|| "missing".to_string(), |b| format!("0x{:02X}", b));
+                    egui::show_tooltip_at_pointer(ui.ctx(), egui::Id::new("hexdiff_tip"), |ui| {
+                        ui.label(format!("Address {}", format_addr(addr)));
+                        ui.label(format!("A: {}", show(byte_at(fa, addr))));
+                        ui.label(format!("B: {}", show(byte_at(fb, addr))));
+                    });
+                }
+            }
+        });
+    }
+}
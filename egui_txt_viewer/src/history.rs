@@ -0,0 +1,342 @@
+use log::info;
+
+use crate::{AppState, DisplayBase, ParsedFile, derived::ValueView};
+
+// Oldest entries are dropped beyond this many undo steps
+const HISTORY_LIMIT: usize = 100;
+
+// View settings tracked by undo/redo
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub(crate) struct ViewSettings {
+    display_base: DisplayBase,
+    show_diff_column: bool,
+    value_view: ValueView,
+    baseline_file: usize,
+    unique_variants: bool,
+}
+
+// Per-file address layout and group tag, snapshotted around layout / rebase / tagging changes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct FileLayout {
+    offset: i64,
+    include_range: Option<(u64, u64)>,
+    group: String,
+}
+
+// One undoable step. File vectors hold the files while they are out of AppState::files
+pub(crate) enum Action {
+    AddFiles { start: usize, count: usize, files: Vec<ParsedFile> },
+    // (index, file) in ascending index order
+    RemoveFiles { removed: Vec<(usize, ParsedFile)> },
+    Clear { files: Vec<ParsedFile> },
+    // Files reordered in place: position i now holds the file that was at order[i]
+    Reorder { order: Vec<usize> },
+    // Offsets, include-ranges and group tags of every file; what names the change for the menu
+    Layout { what: &'static str, before: Vec<FileLayout>, after: Vec<FileLayout> },
+    // Cell edit at an original address: (data, was edited) before and after
+    Edit { file_idx: usize, original: u64, before: (String, bool), after: (String, bool) },
+    Settings { before: ViewSettings, after: ViewSettings },
+}
+
+impl Action {
+    fn label(&self) -> String {
+        match self {
+            Action::AddFiles { count, .. } => format!("add {} file(s)", count),
+            Action::RemoveFiles { removed } => format!("remove {} file(s)", removed.len()),
+            Action::Clear { .. } => "clear".to_string(),
+            Action::Reorder { .. } => "reorder files".to_string(),
+            Action::Layout { what, .. } => what.to_string(),
+            Action::Edit { .. } => "cell edit".to_string(),
+            Action::Settings { .. } => "settings change".to_string(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct History {
+    undo: Vec<Action>,
+    redo: Vec<Action>,
+    // Settings as of the last recorded step, to detect changes made through the UI
+    settings: ViewSettings,
+}
+
+impl History {
+    pub(crate) fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub(crate) fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub(crate) fn undo_label(&self) -> Option<String> {
+        self.undo.last().map(Action::label)
+    }
+
+    pub(crate) fn redo_label(&self) -> Option<String> {
+        self.redo.last().map(Action::label)
+    }
+}
+
+impl AppState {
+    fn view_settings(&self) -> ViewSettings {
+        ViewSettings {
+            display_base: self.display_base,
+            show_diff_column: self.show_diff_column,
+            value_view: self.value_view,
+            baseline_file: self.baseline_file,
+            unique_variants: self.unique_variants,
+        }
+    }
+
+    fn set_view_settings(&mut self, s: &ViewSettings) {
+        self.display_base = s.display_base;
+        self.show_diff_column = s.show_diff_column;
+        self.value_view = s.value_view;
+        self.baseline_file = s.baseline_file;
+        self.unique_variants = s.unique_variants;
+        self.clamp_baseline();
+    }
+
+    // Keep the baseline index valid after files went away. The clamp is written into the tracked
+    // settings too, so it never shows up as a settings change of its own.
+    fn clamp_baseline(&mut self) {
+        self.baseline_file = self.baseline_file.min(self.files.len().saturating_sub(1));
+        self.history.settings = self.view_settings();
+    }
+
+    pub(crate) fn file_layouts(&self) -> Vec<FileLayout> {
+        self.files
+            .iter()
+            .map(|pf| FileLayout { offset: pf.offset, include_range: pf.include_range, group: pf.group.clone() })
+            .collect()
+    }
+
+    // Record the layout / group changes made since the before snapshot as one step
+    pub(crate) fn record_layouts(&mut self, what: &'static str, before: Vec<FileLayout>) {
+        let after = self.file_layouts();
+        if before != after {
+            self.record(Action::Layout { what, before, after });
+        }
+    }
+
+    // Record a new step; any redo history is discarded
+    pub(crate) fn record(&mut self, action: Action) {
+        self.history.redo.clear();
+        self.history.undo.push(action);
+        if self.history.undo.len() > HISTORY_LIMIT {
+            self.history.undo.remove(0);
+        }
+    }
+
+    // Record the files appended since start as one step
+    pub(crate) fn record_added(&mut self, start: usize) {
+        if self.files.len() > start {
+            self.record(Action::AddFiles { start, count: self.files.len() - start, files: Vec::new() });
+        }
+    }
+
+    // Called once per frame: turns settings changed through the UI into an undo step
+    pub(crate) fn track_settings(&mut self) {
+        let current = self.view_settings();
+        if current != self.history.settings {
+            let before = std::mem::replace(&mut self.history.settings, current.clone());
+            self.record(Action::Settings { before, after: current });
+        }
+    }
+
+    // Move the files into a new order (position i takes the file at order[i]) as one undo step
+    pub(crate) fn reorder_files(&mut self, order: Vec<usize>) {
+        if order.iter().enumerate().all(|(pos, &idx)| pos == idx) {
+            return;
+        }
+        let action = self.apply_action(Action::Reorder { order }, false);
+        self.record(action);
+    }
+
+    // File indices changed: drop views that refer to files by index
+    pub(crate) fn files_changed(&mut self) {
+        self.pair_diff = None;
+        self.hexdiff = None;
+        self.editing = None;
+        self.clamp_baseline();
+        self.recalc_intersection();
+    }
+
+    pub(crate) fn undo(&mut self) {
+        let Some(action) = self.history.undo.pop() else { return };
+        info!("Undo {}", action.label());
+        let action = self.apply_action(action, true);
+        self.history.redo.push(action);
+    }
+
+    pub(crate) fn redo(&mut self) {
+        let Some(action) = self.history.redo.pop() else { return };
+        info!("Redo {}", action.label());
+        let action = self.apply_action(action, false);
+        self.history.undo.push(action);
+    }
+
+    // Revert (undo = true) or re-apply an action; returns it ready for the opposite stack
+    fn apply_action(&mut self, action: Action, undo: bool) -> Action {
+        match action {
+            Action::AddFiles { start, count, mut files } => {
+                if undo {
+                    files = self.files.drain(start..(start + count).min(self.files.len())).collect();
+                } else {
+                    let at = start.min(self.files.len());
+                    self.files.splice(at..at, files.drain(..));
+                }
+                self.files_changed();
+                Action::AddFiles { start, count, files }
+            }
+            Action::RemoveFiles { mut removed } => {
+                if undo {
+                    for (idx, pf) in removed.iter_mut() {
+                        let at = (*idx).min(self.files.len());
+                        self.files.insert(at, std::mem::take(pf));
+                    }
+                } else {
+                    for (idx, pf) in removed.iter_mut().rev() {
+                        if *idx < self.files.len() {
+                            *pf = self.files.remove(*idx);
+                        }
+                    }
+                }
+                self.files_changed();
+                Action::RemoveFiles { removed }
+            }
+            Action::Clear { mut files } => {
+                std::mem::swap(&mut self.files, &mut files);
+                self.consensus = None;
+                self.files_changed();
+                Action::Clear { files }
+            }
+            Action::Reorder { order } => {
+                if order.len() == self.files.len() {
+                    let mut slots: Vec<Option<ParsedFile>> = self.files.drain(..).map(Some).collect();
+                    self.files = if undo {
+                        let mut restored: Vec<Option<ParsedFile>> = (0..order.len()).map(|_| None).collect();
+                        for (pos, &from) in order.iter().enumerate() {
+                            restored[from] = slots[pos].take();
+                        }
+                        restored.into_iter().flatten().collect()
+                    } else {
+                        order.iter().filter_map(|&from| slots[from].take()).collect()
+                    };
+                }
+                self.files_changed();
+                Action::Reorder { order }
+            }
+            Action::Layout { what, before, after } => {
+                let layouts = if undo { &before } else { &after };
+                if layouts.len() == self.files.len() {
+                    for (pf, layout) in self.files.iter_mut().zip(layouts) {
+                        pf.offset = layout.offset;
+                        pf.include_range = layout.include_range;
+                        pf.group = layout.group.clone();
+                        pf.apply_layout();
+                    }
+                    self.recalc_intersection();
+                }
+                Action::Layout { what, before, after }
+            }
+            Action::Edit { file_idx, original, before, after } => {
+                let (data, edited) = if undo { &before } else { &after };
+                if let Some(pf) = self.files.get_mut(file_idx) {
+                    pf.original_data.insert(original, data.clone());
+                    if *edited {
+                        pf.edited.insert(original);
+                    } else {
+                        pf.edited.remove(&original);
+                    }
+                    pf.apply_layout();
+                    self.recalc_intersection();
+                }
+                Action::Edit { file_idx, original, before, after }
+            }
+            Action::Settings { before, after } => {
+                self.set_view_settings(if undo { &before } else { &after });
+                Action::Settings { before, after }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsed_file;
+
+    fn state_with(names: &[&str]) -> AppState {
+        let mut state = AppState::default();
+        for name in names {
+            state.files.push(ParsedFile { file_name: name.to_string(), ..parsed_file(&[(0, "00")]) });
+        }
+        state
+    }
+
+    fn names(state: &AppState) -> Vec<&str> {
+        state.files.iter().map(|pf| pf.file_name.as_str()).collect()
+    }
+
+    #[test]
+    fn undo_after_reorder_edits_the_original_file() {
+        let mut state = state_with(&["a", "b", "c"]);
+        state.files[2].original_data.insert(0, "11".to_string());
+        state.files[2].edited.insert(0);
+        state.record(Action::Edit { file_idx: 2, original: 0, before: ("00".to_string(), false), after: ("11".to_string(), true) });
+
+        state.reorder_files(vec![2, 0, 1]);
+        assert_eq!(names(&state), ["c", "a", "b"]);
+
+        state.undo();
+        assert_eq!(names(&state), ["a", "b", "c"]);
+        state.undo();
+        assert_eq!(state.files[2].original_data[&0], "00");
+        assert!(state.files[2].edited.is_empty());
+        assert!(state.files.iter().all(|pf| pf.original_data[&0] == "00"));
+
+        state.redo();
+        state.redo();
+        assert_eq!(names(&state), ["c", "a", "b"]);
+        assert_eq!(state.files[0].original_data[&0], "11");
+    }
+
+    #[test]
+    fn undo_reverts_a_rebase_without_touching_earlier_steps() {
+        let mut state = state_with(&["a", "b", "c"]);
+        state.reorder_files(vec![1, 0, 2]);
+        state.rebase_file(0, 0x10);
+        assert_eq!(state.history.undo_label().as_deref(), Some("rebase"));
+        assert_eq!(state.files[0].address_to_data.keys().copied().collect::<Vec<_>>(), vec![0x10]);
+
+        state.undo();
+        assert_eq!(names(&state), ["b", "a", "c"]);
+        assert_eq!(state.files[0].offset, 0);
+        assert_eq!(state.files[0].address_to_data.keys().copied().collect::<Vec<_>>(), vec![0]);
+        assert_eq!(state.intersect_addresses, vec![0]);
+
+        state.redo();
+        assert_eq!(state.files[0].offset, 0x10);
+        assert!(state.intersect_addresses.is_empty());
+    }
+
+    #[test]
+    fn removing_the_baseline_file_is_a_single_step() {
+        let mut state = state_with(&["a", "b", "c"]);
+        state.baseline_file = 2;
+        state.track_settings();
+        state.files_to_remove = vec![2];
+        state.remove_files();
+        state.track_settings();
+        assert_eq!(state.baseline_file, 1);
+        assert_eq!(state.history.undo_label().as_deref(), Some("remove 1 file(s)"));
+
+        state.undo();
+        assert_eq!(names(&state), ["a", "b", "c"]);
+        assert_eq!(state.history.undo_label().as_deref(), Some("settings change"));
+        state.undo();
+        assert_eq!(state.baseline_file, 0);
+    }
+}
@@ -0,0 +1,326 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::export::base_name;
+use crate::{DisplayBase, ParsedFile, format_addr, parse_data_value, parse_txt_file, parse_user_number};
+
+// Extensions accepted by Add and drag-and-drop
+pub(crate) const EXTENSIONS: [&str; 3] = ["txt", "csv", "tsv"];
+
+// Extra columns written by Export next to the data columns; not file data
+const EXTRA_COLUMNS: [&str; 3] = ["diff", "consensus", "notes"];
+// Header suffixes of derived (XOR / Delta) exports; those values are not the file's data
+const DERIVED_SUFFIXES: [&str; 2] = [" (XOR)", " (Delta)"];
+// Cells that mean "no value at this address" in tables without an Export header
+// (Export's default union marker and derived "?")
+const EMPTY_MARKERS: [&str; 3] = ["", "-", "?"];
+
+// Load one path: .csv / .tsv through the table importer (one file per value column), anything else as a dump
+pub(crate) fn load_path(path: &Path) -> anyhow::Result<Vec<ParsedFile>> {
+    let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("").to_ascii_lowercase();
+    match ext.as_str() {
+        "csv" | "tsv" => import_table(path),
+        _ => Ok(vec![parse_txt_file(path.to_string_lossy().as_ref())?]),
+    }
+}
+
+// Split delimited text into records with their 1-based starting line; quoted fields may hold
+// delimiters, doubled quotes and line breaks
+fn split_records(text: &str, delim: char) -> Vec<(usize, Vec<String>)> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let (mut line, mut start_line) = (1, 1);
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            '\r' => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                records.push((start_line, std::mem::take(&mut fields)));
+                line += 1;
+                start_line = line;
+            }
+            c if c == delim => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push((start_line, fields));
+    }
+    records.retain(|(_, r)| r.iter().any(|f| !f.trim().is_empty()));
+    records
+}
+
+// Addresses are hex like in the dumps: 0x10, 10h or bare 0010
+fn parse_address(text: &str) -> Option<u64> {
+    let t = text.trim();
+    let lower = t.to_ascii_lowercase();
+    if lower.starts_with("0x") || lower.ends_with('h') {
+        return parse_user_number(&lower);
+    }
+    u64::from_str_radix(t, 16).ok()
+}
+
+// Address cell of a first row that makes the row a header: Export's address header, text that is
+// not an address, or a bare word without any digit ("A", "add"), which would otherwise read as hex
+fn is_header_cell(cell: &str) -> bool {
+    let lower = cell.trim().to_ascii_lowercase();
+    let marked = lower.starts_with("0x") || lower.ends_with('h');
+    parse_address_header(cell).is_some() || parse_address(cell).is_none() || (!marked && !lower.chars().any(|c| c.is_ascii_digit()))
+}
+
+// Base and empty marker recorded by Export in the address header, e.g. "address (base=dec; empty=-)"
+fn parse_address_header(cell: &str) -> Option<(DisplayBase, String)> {
+    let inner = cell.trim().strip_prefix("address (")?.strip_suffix(')')?;
+    let (base, marker) = inner.strip_prefix("base=")?.split_once("; empty=")?;
+    let base = [DisplayBase::Hex, DisplayBase::Dec, DisplayBase::Bin].into_iter().find(|&b| base_name(b) == base)?;
+    Some((base, marker.to_string()))
+}
+
+// Convert a cell to the dump's data form (upper-case hex digits); 0x / h marks win over the
+// column base, non-numeric text is kept as written
+fn to_data(cell: &str, base: DisplayBase) -> String {
+    let t = cell.trim();
+    let lower = t.to_ascii_lowercase();
+    let value = if lower.starts_with("0x") || lower.ends_with('h') {
+        parse_data_value(t)
+    } else {
+        match base {
+            DisplayBase::Hex => u64::from_str_radix(t, 16).ok(),
+            DisplayBase::Dec => t.parse::<u64>().ok(),
+            DisplayBase::Bin => u64::from_str_radix(t, 2).ok(),
+        }
+    };
+    value.map_or_else(|| t.to_string(), |v| format!("{:02X}", v))
+}
+
+// Import a CSV / TSV table: Export's layout (address header plus one column per file) or a
+// generic address,value[,value...] file with or without a header row. Values are read in the base
+// Export recorded in the address header; without one, bare values are hex like in the dumps.
+pub(crate) fn import_table(path: &Path) -> anyhow::Result<Vec<ParsedFile>> {
+    let text = fs::read_to_string(path)?;
+    let text = text.strip_prefix('\u{feff}').unwrap_or(&text);
+    let table_name = path.file_name().and_then(|s| s.to_str()).unwrap_or("table").to_string();
+    let is_tsv = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("tsv"));
+    let first_line = text.lines().next().unwrap_or("");
+    let delim = if is_tsv || (first_line.contains('\t') && !first_line.contains(',')) { '\t' } else { ',' };
+    let mut records = split_records(text, delim).into_iter();
+
+    let Some((first_line, first)) = records.next() else { anyhow::bail!("{} is empty", table_name) };
+    let (header, pending) = if first.first().is_none_or(|a| is_header_cell(a)) { (Some(first), None) } else { (None, Some((first_line, first))) };
+    let rows: Vec<(usize, Vec<String>)> = pending.into_iter().chain(records).collect();
+    let recorded = header.as_ref().and_then(|h| h.first()).and_then(|a| parse_address_header(a));
+    let width = header.as_ref().map_or_else(|| rows.iter().map(|(_, r)| r.len()).max().unwrap_or(0), |h| h.len());
+    if width < 2 {
+        anyhow::bail!("{} needs an address column and at least one value column", table_name);
+    }
+
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+    let mut skipped = Vec::new();
+    if recorded.is_none() {
+        skipped.push("no base recorded in the header; values without 0x / h were read as hex".to_string());
+    }
+    let is_empty = |cell: &str| match &recorded {
+        Some((_, marker)) => cell.trim().is_empty() || cell.trim() == marker.trim(),
+        None => EMPTY_MARKERS.contains(&cell.trim()),
+    };
+    let base = recorded.as_ref().map_or(DisplayBase::Hex, |(base, _)| *base);
+    // (column index, name) of the value columns to import
+    let mut columns = Vec::new();
+    for col in 1..width {
+        let name = header.as_ref().and_then(|h| h.get(col)).map(|s| s.trim().to_string()).unwrap_or_default();
+        if EXTRA_COLUMNS.contains(&name.to_ascii_lowercase().as_str()) {
+            continue;
+        }
+        if DERIVED_SUFFIXES.iter().any(|s| name.ends_with(s)) {
+            skipped.push(format!("column '{}' holds derived values and was not imported", name));
+            continue;
+        }
+        let name = match (name.is_empty(), width) {
+            (false, _) => format!("{} ({})", name, table_name),
+            (true, 2) => table_name.clone(),
+            (true, _) => format!("{} col {}", table_name, col + 1),
+        };
+        columns.push((col, name));
+    }
+    if columns.is_empty() {
+        anyhow::bail!("{} has no value columns to import", table_name);
+    }
+
+    let mut files: Vec<ParsedFile> = columns
+        .iter()
+        .map(|(_, name)| ParsedFile {
+            file_name: name.clone(),
+            path: Some(path.to_path_buf()),
+            modified,
+            diagnostics: skipped.clone(),
+            ..Default::default()
+        })
+        .collect();
+    let mut bad_rows = Vec::new();
+    // First line of each address, to report duplicates
+    let mut seen: BTreeMap<u64, usize> = BTreeMap::new();
+    for (line, row) in &rows {
+        match row.first().and_then(|a| parse_address(a)) {
+            Some(addr) if row.len() <= width => {
+                let first = *seen.entry(addr).or_insert(*line);
+                if first != *line {
+                    bad_rows.push(format!("line {}: duplicate address {} (first on line {}), the later value is used", line, format_addr(addr), first));
+                }
+                for ((col, _), pf) in columns.iter().zip(files.iter_mut()) {
+                    let cell = row.get(*col).map(String::as_str).unwrap_or("");
+                    if !is_empty(cell) {
+                        pf.original_data.insert(addr, to_data(cell, base));
+                    }
+                }
+            }
+            Some(_) => bad_rows.push(format!("line {}: {} fields, expected at most {}", line, row.len(), width)),
+            None => bad_rows.push(format!("line {}: invalid address '{}'", line, row.first().map(String::as_str).unwrap_or(""))),
+        }
+    }
+    for pf in &mut files {
+        pf.diagnostics.extend(bad_rows.iter().cloned());
+        if pf.original_data.is_empty() {
+            pf.diagnostics.push("no data rows found".to_string());
+        }
+        pf.apply_layout();
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AppState, parsed_file};
+    use crate::export::{ExportFormat, ExportOptions, ExportRows};
+
+    fn fields(records: &[(usize, Vec<String>)]) -> Vec<(usize, Vec<&str>)> {
+        records.iter().map(|(line, r)| (*line, r.iter().map(String::as_str).collect())).collect()
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("suffixcode_{}_{}", std::process::id(), name))
+    }
+
+    fn import_text(name: &str, text: &str) -> Vec<ParsedFile> {
+        let path = temp_path(name);
+        fs::write(&path, text).unwrap();
+        let files = import_table(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        files
+    }
+
+    #[test]
+    fn split_records_handles_quotes() {
+        let text = "address,\"a, b\",\"say \"\"hi\"\"\"\n0x10,\"7E\",\"\"\r\n";
+        assert_eq!(fields(&split_records(text, ',')), vec![(1, vec!["address", "a, b", "say \"hi\""]), (2, vec!["0x10", "7E", ""])]);
+    }
+
+    #[test]
+    fn split_records_keeps_line_breaks_in_quotes() {
+        let text = "address,\"two\nlines\"\n\n0x10,01\n0x11,02";
+        assert_eq!(fields(&split_records(text, ',')), vec![(1, vec!["address", "two\nlines"]), (4, vec!["0x10", "01"]), (5, vec!["0x11", "02"])]);
+    }
+
+    #[test]
+    fn split_records_reads_tsv() {
+        let text = "address\ta,b\t\"c\td\"\n0x10\t01\t02\n";
+        assert_eq!(fields(&split_records(text, '\t')), vec![(1, vec!["address", "a,b", "c\td"]), (2, vec!["0x10", "01", "02"])]);
+    }
+
+    #[test]
+    fn address_header_round_trips() {
+        for base in [DisplayBase::Hex, DisplayBase::Dec, DisplayBase::Bin] {
+            for marker in ["-", "", "N/A", "; empty=)"] {
+                assert_eq!(parse_address_header(&crate::export::address_header(base, marker)), Some((base, marker.to_string())));
+            }
+        }
+        assert_eq!(parse_address_header("address"), None);
+    }
+
+    #[test]
+    fn export_then_import_keeps_values_in_every_base() {
+        let mut app = AppState::default();
+        // Values that a guessing importer would misread: 0/1-only hex, digit-only hex
+        for (name, values) in [("golden", [(0x10, "10"), (0x11, "01"), (0x12, "7E")]), ("dut \"A\", v2", [(0x10, "11"), (0x11, "01"), (0x13, "FF")])] {
+            app.files.push(ParsedFile { file_name: name.to_string(), ..parsed_file(&values) });
+        }
+        for (format, ext) in [(ExportFormat::Csv, "csv"), (ExportFormat::Tsv, "tsv")] {
+            for base in [DisplayBase::Hex, DisplayBase::Dec, DisplayBase::Bin] {
+                // "?" is data here, not a missing cell: it is not the recorded marker
+                let options = ExportOptions { rows: ExportRows::Union, use_filter: false, follow_table: false, base, empty_marker: "?!".to_string(), ..Default::default() };
+                let bytes = app.build_export_table(&options).render(format).unwrap();
+                let files = import_text(&format!("export.{}", ext), &String::from_utf8(bytes).unwrap());
+                assert_eq!(files.len(), 2);
+                for (imported, original) in files.iter().zip(&app.files) {
+                    assert_eq!(imported.file_name, format!("{} (suffixcode_{}_export.{})", original.file_name, std::process::id(), ext));
+                    assert_eq!(imported.original_data, original.original_data, "{:?} {:?}", format, base);
+                    assert!(imported.diagnostics.is_empty(), "{:?}", imported.diagnostics);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn custom_marker_is_not_imported_as_data() {
+        let files = import_text("marker.csv", "address (base=dec; empty=N/A),a,b\n0x10,126,N/A\n0x11,-,1\n");
+        assert_eq!(files[0].original_data, BTreeMap::from([(0x10, "7E".to_string()), (0x11, "-".to_string())]));
+        assert_eq!(files[1].original_data, BTreeMap::from([(0x11, "01".to_string())]));
+    }
+
+    #[test]
+    fn tables_without_export_header_read_bare_values_as_hex() {
+        let files = import_text("plain.csv", "0x10,10\n0x11,10101010\n0x12,-\n0x13,12h\n");
+        assert_eq!(files[0].original_data, BTreeMap::from([(0x10, "10".to_string()), (0x11, "10101010".to_string()), (0x13, "12".to_string())]));
+        assert_eq!(files[0].diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn duplicates_point_at_the_first_occurrence() {
+        let files = import_text("dups.csv", "address (base=hex; empty=-),a\n0x10,01\n0x10,02\n0x10,03\n");
+        assert_eq!(files[0].original_data[&0x10], "03");
+        assert_eq!(
+            files[0].diagnostics,
+            [
+                "line 3: duplicate address 0x10 (first on line 2), the later value is used",
+                "line 4: duplicate address 0x10 (first on line 2), the later value is used"
+            ]
+        );
+    }
+
+    #[test]
+    fn detects_generic_header_rows() {
+        for (text, name) in [("A,B\n10,01\n", "B ("), ("add,val\n0x10,01\n", "val ("), ("Addr,Data\n10h,01\n", "Data (")] {
+            let files = import_text("h.csv", text);
+            assert!(files[0].file_name.starts_with(name), "{}", text);
+            assert_eq!(files[0].original_data.len(), 1, "{}", text);
+        }
+        // Without a header, the first row is data, including marked all-letter addresses
+        for text in ["10,01\n11,02\n", "Ah,01\n0x11,02\n"] {
+            let files = import_text("h.csv", text);
+            assert!(!files[0].file_name.contains(" ("), "{}", text);
+            assert_eq!(files[0].original_data.len(), 2, "{}", text);
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use eframe::egui;
+use log::info;
+
+use crate::{AppState, ParsedFile, format_addr, parse_user_number};
+
+impl ParsedFile {
+    // Rebuild address_to_data from the parsed rows: crop to include_range, then add offset
+    pub(crate) fn apply_layout(&mut self) {
+        self.address_to_data = self
+            .original_data
+            .iter()
+            .filter(|(addr, _)| self.include_range.is_none_or(|(start, end)| (start..=end).contains(*addr)))
+            .filter_map(|(addr, data)| Some((addr.checked_add_signed(self.offset)?, data.clone())))
+            .collect();
+    }
+
+    // Address in the source file for a displayed address
+    pub(crate) fn original_addr(&self, addr: u64) -> Option<u64> {
+        addr.checked_add_signed(self.offset.checked_neg()?)
+    }
+}
+
+// Signed user number: optional leading '-' before any form parse_user_number accepts
+fn parse_offset(text: &str) -> Option<i64> {
+    let t = text.trim();
+    match t.strip_prefix('-') {
+        Some(rest) => i64::try_from(parse_user_number(rest)?).ok().map(|v| -v),
+        None => i64::try_from(parse_user_number(t.trim_start_matches('+'))?).ok(),
+    }
+}
+
+fn format_offset(offset: i64) -> String {
+    if offset < 0 { format!("-{}", format_addr(offset.unsigned_abs())) } else { format_addr(offset as u64) }
+}
+
+// Text inputs for one file's layout, reloaded from the file whenever the data changes
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LayoutEdit {
+    offset: String,
+    crop: bool,
+    start: String,
+    end: String,
+}
+
+impl LayoutEdit {
+    fn from_file(pf: &ParsedFile) -> Self {
+        let (start, end) = pf.include_range.unwrap_or((
+            pf.original_data.keys().next().copied().unwrap_or(0),
+            pf.original_data.keys().next_back().copied().unwrap_or(0),
+        ));
+        Self { offset: format_offset(pf.offset), crop: pf.include_range.is_some(), start: format_addr(start), end: format_addr(end) }
+    }
+
+    fn parse(&self) -> Option<(i64, Option<(u64, u64)>)> {
+        let offset = parse_offset(&self.offset)?;
+        let range = if self.crop {
+            let (start, end) = (parse_user_number(&self.start)?, parse_user_number(&self.end)?);
+            if end < start {
+                return None;
+            }
+            Some((start, end))
+        } else {
+            None
+        };
+        Some((offset, range))
+    }
+}
+
+impl AppState {
+    pub(crate) fn show_layout_window(&mut self, ctx: &egui::Context) {
+        let main_rect = ctx.input(|i| i.screen_rect());
+        let mut open = self.show_layout;
+        egui::Window::new("Address Layout")
+            .constrain_to(main_rect)
+            .max_size(main_rect.size())
+            .open(&mut open)
+            .show(ctx, |ui| self.layout_ui(ui));
+        self.show_layout = open;
+    }
+
+    fn layout_ui(&mut self, ui: &mut egui::Ui) {
+        if self.files.is_empty() {
+            ui.label("No files loaded.");
+            return;
+        }
+        if self.layout_edits_for != Some(self.data_revision) || self.layout_edits.len() != self.files.len() {
+            self.layout_edits = self.files.iter().map(LayoutEdit::from_file).collect();
+            self.layout_edits_for = Some(self.data_revision);
+        }
+        ui.label("Shown address = original address + offset. The include-range uses original addresses.");
+        ui.separator();
+
+        let mut valid = true;
+        egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+            egui::Grid::new("layout_grid").striped(true).num_columns(5).show(ui, |ui| {
+                for label in ["File", "Offset", "Crop", "Start", "End"] {
+                    ui.strong(label);
+                }
+                ui.end_row();
+                for (pf, edit) in self.files.iter().zip(self.layout_edits.iter_mut()) {
+                    ui.label(&pf.file_name);
+                    ui.add(egui::TextEdit::singleline(&mut edit.offset).desired_width(90.0));
+                    ui.checkbox(&mut edit.crop, "");
+                    ui.add_enabled(edit.crop, egui::TextEdit::singleline(&mut edit.start).desired_width(90.0));
+                    ui.add_enabled(edit.crop, egui::TextEdit::singleline(&mut edit.end).desired_width(90.0));
+                    if edit.parse().is_none() {
+                        valid = false;
+                        ui.colored_label(egui::Color32::RED, "invalid");
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(valid, egui::Button::new("Apply")).clicked() {
+                let before = self.file_layouts();
+                let layouts: Vec<_> = self.layout_edits.iter().filter_map(LayoutEdit::parse).collect();
+                for (pf, (offset, range)) in self.files.iter_mut().zip(layouts) {
+                    pf.offset = offset;
+                    pf.include_range = range;
+                    pf.apply_layout();
+                }
+                info!("Applied address layout to {} files", self.files.len());
+                self.recalc_intersection();
+                self.record_layouts("layout change", before);
+            }
+            if ui.button("Reset All").clicked() {
+                let before = self.file_layouts();
+                for pf in &mut self.files {
+                    pf.offset = 0;
+                    pf.include_range = None;
+                    pf.apply_layout();
+                }
+                self.recalc_intersection();
+                self.record_layouts("layout reset", before);
+            }
+        });
+        if !valid {
+            ui.colored_label(egui::Color32::RED, "Enter valid numbers (offsets may start with '-'; Start must not exceed End).");
+        }
+    }
+
+    // Shift a file's displayed addresses by delta on top of its current offset
+    pub(crate) fn rebase_file(&mut self, file_idx: usize, delta: i64) {
+        let before = self.file_layouts();
+        let pf = &mut self.files[file_idx];
+        pf.offset = pf.offset.saturating_add(delta);
+        pf.apply_layout();
+        info!("Rebased {} to offset {} ({} rows shown)", pf.file_name, format_offset(pf.offset), pf.address_to_data.len());
+        self.recalc_intersection();
+        self.record_layouts("rebase", before);
+    }
+}
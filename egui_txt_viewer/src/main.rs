@@ -6,6 +6,31 @@ use log::{error, info, warn};
 use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 
+mod bit_heatmap;
+mod chart;
+mod checksum;
+mod consensus;
+mod correlation;
+mod derived;
+mod edit;
+mod export;
+mod filter;
+mod groups;
+mod hexdiff;
+mod history;
+mod import;
+mod layout;
+mod outliers;
+mod overview;
+mod patch;
+mod report;
+mod rules;
+mod search;
+mod shift;
+mod similarity;
+mod trend;
+mod variants;
+
 #[cfg(target_os = "windows")]
 use winapi::um::errhandlingapi::SetUnhandledExceptionFilter;
 #[cfg(target_os = "windows")]
@@ -32,8 +57,38 @@ fn setup_windows_exception_handler() {
 #[derive(Default, Debug, Clone)]
 struct ParsedFile {
     file_name: String,
-    // address -> data
+    // Source path and text as loaded, for saving edits back
+    path: Option<std::path::PathBuf>,
+    source: String,
+    // Original address -> line index in source (as counted by str::lines)
+    line_of: BTreeMap<u64, usize>,
+    // Original addresses changed by cell edits since load / last save
+    edited: BTreeSet<u64>,
+    // Source modification time as loaded
+    modified: Option<std::time::SystemTime>,
+    header_lines: Vec<String>,
+    // address -> data as parsed from the file
+    original_data: BTreeMap<u64, String>,
+    // Address layout applied to original_data (see apply_layout): shown = original + offset
+    offset: i64,
+    include_range: Option<(u64, u64)>,
+    // address -> data after the layout, used by every view
     address_to_data: BTreeMap<u64, String>,
+    // User-assigned group tag (e.g. PASS / FAIL); empty when untagged
+    group: String,
+    // Parser notes for the report: skipped or unreadable lines, duplicate addresses
+    diagnostics: Vec<String>,
+}
+
+// Test fixture: a file holding the given (address, data) rows with the default layout applied
+#[cfg(test)]
+pub(crate) fn parsed_file(rows: &[(u64, &str)]) -> ParsedFile {
+    let mut pf = ParsedFile::default();
+    for (addr, data) in rows {
+        pf.original_data.insert(*addr, data.to_string());
+    }
+    pf.apply_layout();
+    pf
 }
 
 #[derive(Default)]
@@ -41,15 +96,105 @@ struct AppState {
     files: Vec<ParsedFile>,
     // Cached intersection of all addresses across selected files
     intersect_addresses: Vec<u64>,
+    // Bumped whenever files/intersection change; analysis caches compare against it
+    data_revision: u64,
+    // Row filter: compiled expression and the intersect_addresses indices it keeps
+    filter_text: String,
+    filter: Option<filter::Expr>,
+    filter_error: Option<String>,
+    filter_revision: u64,
+    visible_rows: Vec<usize>,
+    visible_for: Option<(u64, u64, DisplayBase)>,
     // UI
     show_stats: bool,
     selected_row: Option<usize>,
+    // Address the main table should scroll to on the next frame
+    scroll_to_addr: Option<u64>,
     display_base: DisplayBase,
     stats_metric: StatsMetric,
     chart_alpha: f32,
     files_to_remove: Vec<usize>,
     show_diff_column: bool,
     show_pie_chart: bool,
+    // Undo/redo for file add/remove, Clear, cell edits and view settings
+    history: history::History,
+    // Data cell being edited (double-click a cell in Raw view)
+    editing: Option<edit::CellEdit>,
+    // Data columns show raw values or XOR / delta against the baseline file
+    value_view: derived::ValueView,
+    baseline_file: usize,
+    // Identical files merged into one column per unique variant; expanded variants show every member
+    unique_variants: bool,
+    variants: Option<variants::Variants>,
+    expanded_variants: BTreeSet<u64>,
+    // Similarity matrix window
+    show_similarity: bool,
+    similarity_metric: similarity::DistanceMetric,
+    similarity: Option<similarity::SimilarityMatrix>,
+    // Two-file diff opened from the similarity heatmap: (file a, file b)
+    pair_diff: Option<(usize, usize)>,
+    // Side-by-side hex dump of two files, opened from a file column header
+    hexdiff: Option<hexdiff::HexDiff>,
+    // Virtual majority-vote column; None until built via the Consensus command
+    show_consensus: bool,
+    consensus: Option<consensus::Consensus>,
+    // Per-file disagreement ranking
+    show_outliers: bool,
+    outliers: Option<outliers::OutlierReport>,
+    outlier_sort: SortState,
+    // Statistics window: selected-address charts or whole-dump overview table
+    stats_tab: StatsTab,
+    stats_overview: Option<overview::StatsOverview>,
+    overview_sort: SortState,
+    // Group tagging and group-vs-group separation ranking
+    show_groups: bool,
+    group_a: String,
+    group_b: String,
+    group_tag_input: String,
+    // Tags as they were when a group name field took focus, recorded as one step on leaving it
+    group_edit_before: Option<Vec<history::FileLayout>>,
+    group_comparison: Option<groups::GroupComparison>,
+    group_selected: Option<u64>,
+    // Time-ordered trend view
+    show_trend: bool,
+    trend_source: trend::TimeSource,
+    trend_name_pattern: String,
+    trend_header_key: String,
+    // Address co-variation analysis
+    show_correlation: bool,
+    correlation_metric: correlation::LinkMetric,
+    correlation_limit: usize,
+    correlation: Option<correlation::CorrelationCache>,
+    // Per-bit probability heatmap
+    show_bit_heatmap: bool,
+    bit_heatmap: Option<bit_heatmap::BitHeatmap>,
+    // Checksum / CRC over an address range
+    show_checksum: bool,
+    checksum_config: checksum::ChecksumConfig,
+    checksum_table: Option<checksum::ChecksumTable>,
+    // Expected-value rules and their evaluation against every file
+    show_rules: bool,
+    rules_text: String,
+    rules: Vec<rules::Rule>,
+    rule_errors: Vec<String>,
+    rules_revision: u64,
+    rule_report: Option<rules::RuleReport>,
+    // Address shift detection between two files
+    show_shift: bool,
+    shift: shift::ShiftState,
+    // Per-file address offset / include-range editor
+    show_layout: bool,
+    layout_edits: Vec<layout::LayoutEdit>,
+    layout_edits_for: Option<u64>,
+    // Patch creation (A -> B) and application
+    show_patch: bool,
+    patch: patch::PatchState,
+    // Go-to-address and value search
+    show_search: bool,
+    search: search::SearchState,
+    // Export dialog (format, rows, columns, base)
+    show_export: bool,
+    export_options: export::ExportOptions,
 }
 
 impl AppState {
@@ -60,11 +205,16 @@ impl AppState {
             chart_alpha: 0.8, 
             show_diff_column: false, 
             show_pie_chart: false,
+            outlier_sort: SortState { column: 2, descending: true },
+            trend_name_pattern: r"\d{8}[_-]?\d{6}|\d+".to_string(),
+            trend_header_key: "Date".to_string(),
+            correlation_limit: 20,
             ..Default::default() 
         }
     }
 
     fn recalc_intersection(&mut self) {
+        self.data_revision += 1;
         let mut iter = self.files.iter();
         let Some(first) = iter.next() else {
             self.intersect_addresses.clear();
@@ -78,35 +228,63 @@ impl AppState {
         self.intersect_addresses = set.into_iter().collect();
     }
 
+    // Address of the selected table row, if any
+    fn selected_addr(&self) -> Option<u64> {
+        self.selected_row.and_then(|r| self.intersect_addresses.get(r)).copied()
+    }
+
+    fn select_addr(&mut self, addr: u64) {
+        self.selected_row = self.intersect_addresses.iter().position(|&a| a == addr);
+        self.scroll_to_addr = self.selected_row.map(|_| addr);
+    }
+
     fn remove_files(&mut self) {
         if !self.files_to_remove.is_empty() {
             // Sort indices in descending order to avoid shifting issues
             self.files_to_remove.sort_by(|a, b| b.cmp(a));
+            self.files_to_remove.dedup();
+            let mut removed = Vec::new();
             for &index in &self.files_to_remove {
                 if index < self.files.len() {
-                    self.files.remove(index);
+                    removed.push((index, self.files.remove(index)));
                 }
             }
             self.files_to_remove.clear();
-            self.recalc_intersection();
+            // Kept for undo in ascending order
+            removed.reverse();
+            if !removed.is_empty() {
+                self.record(history::Action::RemoveFiles { removed });
+            }
+            self.files_changed();
         }
     }
 }
 
 
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum DisplayBase { Hex, Bin, Dec }
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+enum DisplayBase { #[default] Hex, Bin, Dec }
 
-impl Default for DisplayBase {
-    fn default() -> Self { DisplayBase::Hex }
-}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+enum StatsMetric { Percent, #[default] Count }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+enum StatsTab { #[default] Selected, Overview }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum StatsMetric { Percent, Count }
+// Sort column and direction of a sortable table
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+struct SortState { column: usize, descending: bool }
 
-impl Default for StatsMetric {
-    fn default() -> Self { StatsMetric::Count }
+// Clickable column header: clicking the active column flips direction, others sort ascending
+fn sort_header(ui: &mut egui::Ui, label: &str, column: usize, sort: &mut SortState) {
+    let arrow = if sort.column != column { "" } else if sort.descending { " ⬇" } else { " ⬆" };
+    if ui.button(format!("{}{}", label, arrow)).clicked() {
+        if sort.column == column {
+            sort.descending = !sort.descending;
+        } else {
+            *sort = SortState { column, descending: false };
+        }
+    }
 }
 
 // Generate a convex polygon approximating a pie slice from start_angle to end_angle
@@ -163,6 +341,29 @@ fn format_data_with_base(raw: &str, base: DisplayBase) -> String {
     }
 }
 
+// Parse a data cell the same way format_data_with_base does (hex first, then decimal)
+fn parse_data_value(raw: &str) -> Option<u64> {
+    let cleaned = raw.trim().trim_end_matches('h');
+    u64::from_str_radix(cleaned.trim_start_matches("0x"), 16)
+        .ok()
+        .or_else(|| cleaned.parse::<u64>().ok())
+}
+
+// Parse a number typed by the user: 0x1F / 1Fh (hex), 0b1010 (binary), otherwise decimal
+fn parse_user_number(text: &str) -> Option<u64> {
+    let t = text.trim().replace('_', "");
+    let lower = t.to_ascii_lowercase();
+    if let Some(hex) = lower.strip_prefix("0x") {
+        u64::from_str_radix(hex, 16).ok()
+    } else if let Some(bin) = lower.strip_prefix("0b") {
+        u64::from_str_radix(bin, 2).ok()
+    } else if let Some(hex) = lower.strip_suffix('h') {
+        u64::from_str_radix(hex, 16).ok()
+    } else {
+        lower.parse::<u64>().ok()
+    }
+}
+
 fn format_hex_prefixed_min2_even(v: u64) -> String {
     let mut s = format!("{:x}", v);
     if s.len() < 2 { s = format!("{:02x}", v); }
@@ -193,6 +394,25 @@ fn is_data_different(files: &[ParsedFile], addr: u64, display_base: DisplayBase)
     false
 }
 
+// Quote a CSV field when it contains a delimiter, quote or line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// Group file indices by their displayed value at addr (value_string -> Vec<file index>)
+fn group_files_by_value(files: &[ParsedFile], addr: u64, display_base: DisplayBase) -> BTreeMap<String, Vec<usize>> {
+    let mut groups: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (idx, pf) in files.iter().enumerate() {
+        let raw = pf.address_to_data.get(&addr).cloned().unwrap_or_default();
+        groups.entry(format_data_with_base(&raw, display_base)).or_default().push(idx);
+    }
+    groups
+}
+
 // Generate N distinct colors by evenly spacing hues on the HSV circle
 fn generate_palette(count: usize) -> Vec<egui::Color32> {
     if count == 0 { return Vec::new(); }
@@ -236,6 +456,10 @@ fn load_app_icon() -> Option<egui::IconData> { None }
 fn parse_txt_file(path: &str) -> anyhow::Result<ParsedFile> {
     let content = fs::read_to_string(path)?;
     let mut address_to_data: BTreeMap<u64, String> = BTreeMap::new();
+    let mut line_of: BTreeMap<u64, usize> = BTreeMap::new();
+    // Non-data lines before the first data row (e.g. tester name, date)
+    let mut header_lines: Vec<String> = Vec::new();
+    let mut diagnostics: Vec<String> = Vec::new();
     for (idx, line) in content.lines().enumerate() {
         let trimmed = line.trim();
         if trimmed.is_empty() {
@@ -247,6 +471,11 @@ fn parse_txt_file(path: &str) -> anyhow::Result<ParsedFile> {
         // split by tab
         let parts: Vec<&str> = trimmed.split('\t').collect();
         if parts.len() < 3 {
+            if address_to_data.is_empty() {
+                header_lines.push(trimmed.to_string());
+            } else {
+                diagnostics.push(format!("line {}: fewer than 3 columns: {}", idx + 1, trimmed));
+            }
             warn!("跳过第{idx}行：列数不足: {trimmed}");
             continue;
         }
@@ -257,6 +486,7 @@ fn parse_txt_file(path: &str) -> anyhow::Result<ParsedFile> {
         let addr_str = parts[2].trim();
         let data_str = parts.get(5).map(|s| s.trim()).unwrap_or("");
         if addr_str.is_empty() || data_str.is_empty() {
+            diagnostics.push(format!("line {}: empty address or data: {}", idx + 1, trimmed));
             warn!("跳过第{idx}行：地址或数据为空: {trimmed}");
             continue;
         }
@@ -265,19 +495,55 @@ fn parse_txt_file(path: &str) -> anyhow::Result<ParsedFile> {
         let addr_clean = addr_str.trim_end_matches('h').trim_start_matches("0x");
         match u64::from_str_radix(addr_clean, 16) {
             Ok(address) => {
+                if let Some(&first) = line_of.get(&address) {
+                    diagnostics.push(format!("line {}: duplicate address {} (first on line {}), the later value is used", idx + 1, format_addr(address), first + 1));
+                    warn!("重复地址 第{idx}行: {addr_str}");
+                }
                 address_to_data.insert(address, data_str.to_string());
+                line_of.insert(address, idx);
             }
             Err(e) => {
+                diagnostics.push(format!("line {}: invalid address '{}': {}", idx + 1, addr_str, e));
                 warn!("解析地址失败 第{idx}行: {addr_str}, 错误: {e}");
             }
         }
     }
+    if address_to_data.is_empty() {
+        diagnostics.push("no data rows found".to_string());
+    }
     let file_name = std::path::Path::new(path)
         .file_name()
         .and_then(|s| s.to_str())
         .unwrap_or(path)
         .to_string();
-    Ok(ParsedFile { file_name, address_to_data })
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+    Ok(ParsedFile {
+        file_name,
+        path: Some(std::path::PathBuf::from(path)),
+        line_of,
+        modified,
+        header_lines,
+        diagnostics,
+        original_data: address_to_data.clone(),
+        address_to_data,
+        source: content,
+        ..Default::default()
+    })
+}
+
+// Write rows in the layout parse_txt_file reads back (column 3 = address, column 6 = data)
+// 样例：0001\t0\t02\t02\t02h\t7E\t126\t
+fn write_suffix_code<'a>(rows: impl IntoIterator<Item = (u64, &'a str)>) -> String {
+    let mut out = String::new();
+    for (idx, (addr, raw)) in rows.into_iter().enumerate() {
+        let (data, dec) = match parse_data_value(raw) {
+            Some(v) => (format!("{:02X}", v), v.to_string()),
+            None => (raw.trim().to_string(), String::new()),
+        };
+        out.push_str(&format!("{:04}\t0\t{:02X}\t{:02X}\t{:02X}h\t{}\t{}\t\n", idx + 1, addr, addr, addr, data, dec));
+    }
+    out.push_str("END\n");
+    out
 }
 
 fn main() -> eframe::Result<()> {
@@ -297,16 +563,11 @@ fn main() -> eframe::Result<()> {
     }));
     
     let mut native_options = eframe::NativeOptions::default();
-    #[cfg(target_os = "windows")]
-    {
-        if let Some(icon) = load_app_icon() {
-            native_options.viewport.icon = Some(icon.into());
-        }
+    if let Some(icon) = load_app_icon() {
+        native_options.viewport.icon = Some(icon.into());
     }
     
     // 实现真正的渲染器降级逻辑：通过特性控制和运行时检测
-    let mut last_error = None;
-    
     // 首先尝试使用默认配置（eframe 会自动选择最佳可用渲染器）
     info!("Trying default renderer (auto-selection)...");
     match eframe::run_native(
@@ -320,7 +581,6 @@ fn main() -> eframe::Result<()> {
         }
         Err(e) => {
             warn!("Default renderer failed: {:?}, trying with specific features...", e);
-            last_error = Some(("Default", e));
         }
     }
     
@@ -340,7 +600,6 @@ fn main() -> eframe::Result<()> {
         }
         Err(e) => {
             warn!("Glow renderer failed: {:?}, trying minimal configuration...", e);
-            last_error = Some(("Glow", e));
         }
     }
     
@@ -351,7 +610,7 @@ fn main() -> eframe::Result<()> {
     minimal_options.viewport.min_inner_size = Some(egui::vec2(800.0, 600.0));
     minimal_options.viewport.max_inner_size = Some(egui::vec2(1920.0, 1080.0));
     
-    match eframe::run_native(
+    let last_error = match eframe::run_native(
         "SuffixCode Viewer (Minimal)",
         minimal_options,
         Box::new(|_cc| Box::new(AppState::new())),
@@ -362,27 +621,27 @@ fn main() -> eframe::Result<()> {
         }
         Err(e) => {
             warn!("Minimal renderer failed: {:?}", e);
-            last_error = Some(("Minimal", e));
+            Some(("Minimal", e))
         }
-    }
+    };
     
     // 如果所有后端都失败，显示详细的错误信息和故障排除建议
     if let Some((renderer_name, e)) = last_error {
         error!("All renderers failed. Last error from {}: {:?}", renderer_name, e);
         eprintln!("Failed to start application with any renderer.");
-        eprintln!("");
+        eprintln!();
         eprintln!("Error details:");
         eprintln!("- Default renderer (auto-selection): Failed");
         eprintln!("- Glow renderer (OpenGL): Failed");
         eprintln!("- Minimal renderer: Failed");
-        eprintln!("");
+        eprintln!();
         eprintln!("This may be due to:");
         eprintln!("1. Graphics drivers are outdated or corrupted");
         eprintln!("2. OpenGL version is too low (need 2.0+)");
         eprintln!("3. Graphics hardware doesn't support required features");
         eprintln!("4. System libraries are missing or incompatible");
         eprintln!("5. Virtual machine or remote desktop limitations");
-        eprintln!("");
+        eprintln!();
         eprintln!("Please try the following solutions:");
         eprintln!("1. Update your graphics drivers to the latest version");
         eprintln!("2. Check if your GPU supports OpenGL 2.0+ or Vulkan");
@@ -390,7 +649,7 @@ fn main() -> eframe::Result<()> {
         eprintln!("4. Try running on a different computer or OS");
         eprintln!("5. If on Windows, try compatibility mode or run as administrator");
         eprintln!("6. If in VM, enable 3D acceleration and install guest tools");
-        eprintln!("");
+        eprintln!();
         eprintln!("For more help, check the error log file: renderer_error.txt");
         
         // 写入详细的错误日志
@@ -414,65 +673,115 @@ impl eframe::App for AppState {
                 ui.with_layout(Layout::left_to_right(egui::Align::Center), |ui| {
                     if ui.button("Add").clicked() {
                         let files = rfd::FileDialog::new()
-                            .add_filter("Text", &["txt"]).pick_files();
+                            .add_filter("Dumps and tables", &import::EXTENSIONS)
+                            .add_filter("Text", &["txt"])
+                            .add_filter("CSV / TSV", &["csv", "tsv"]).pick_files();
                         if let Some(paths) = files {
+                            let start = self.files.len();
                             for path in paths {
-                                match parse_txt_file(path.to_string_lossy().as_ref()) {
-                                    Ok(pf) => {
-                                        info!("Parsed: {} ({} rows)", pf.file_name, pf.address_to_data.len());
-                                        self.files.push(pf);
+                                match import::load_path(&path) {
+                                    Ok(parsed) => {
+                                        for pf in parsed {
+                                            info!("Parsed: {} ({} rows)", pf.file_name, pf.address_to_data.len());
+                                            self.files.push(pf);
+                                        }
                                     }
                                     Err(e) => {
                                         error!("Parse failed: {:?}", e);
                                     }
                                 }
                             }
+                            self.record_added(start);
                             self.recalc_intersection();
                         }
                     }
 
                     if ui.button("Clear").clicked() {
-                        self.files.clear();
-                        self.intersect_addresses.clear();
+                        let files = std::mem::take(&mut self.files);
+                        if !files.is_empty() {
+                            self.record(history::Action::Clear { files });
+                        }
                         self.files_to_remove.clear();
+                        self.consensus = None;
+                        self.files_changed();
+                    }
+
+                    let undo_tip = self.history.undo_label().map_or_else(|| "Nothing to undo".to_string(), |l| format!("Undo {} (Ctrl+Z)", l));
+                    if ui.add_enabled(self.history.can_undo(), egui::Button::new("Undo")).on_hover_text(undo_tip).clicked() {
+                        self.undo();
+                    }
+                    let redo_tip = self.history.redo_label().map_or_else(|| "Nothing to redo".to_string(), |l| format!("Redo {} (Ctrl+Y)", l));
+                    if ui.add_enabled(self.history.can_redo(), egui::Button::new("Redo")).on_hover_text(redo_tip).clicked() {
+                        self.redo();
                     }
 
                     if ui.button("Stats").clicked() {
                         self.show_stats = true;
                     }
 
-                    if ui.button("Export").clicked() {
-                        // Export CSV: first column is address, then one column per file's data
-                        if !self.intersect_addresses.is_empty() && !self.files.is_empty() {
-                            let mut csv = String::new();
-                            // header
-                            csv.push_str("address");
-                            for pf in &self.files {
-                                csv.push(',');
-                                csv.push_str(&pf.file_name);
-                            }
-                            csv.push('\n');
-                            // rows
-                            for addr in &self.intersect_addresses {
-                                csv.push_str(&format_addr(*addr));
-                                for pf in &self.files {
-                                    let raw = pf.address_to_data.get(addr).cloned().unwrap_or_default();
-                                    let val = format_data_with_base(&raw, self.display_base);
-                                    csv.push(',');
-                                    csv.push_str(&val);
-                                }
-                                csv.push('\n');
-                            }
-                            if let Some(path) = rfd::FileDialog::new().set_file_name("export.csv").save_file() {
-                                if let Err(e) = fs::write(&path, csv) {
-                                    error!("Export failed: {:?}", e);
-                                } else {
-                                    info!("Exported: {}", path.to_string_lossy());
-                                }
-                            }
-                        } else {
-                            warn!("No data to export");
+                    if ui.button("Search").clicked() {
+                        self.show_search = true;
+                    }
+
+                    if ui.button("Layout").clicked() {
+                        self.show_layout = true;
+                    }
+
+                    ui.menu_button("Analysis", |ui| {
+                        if ui.button("Similarity").clicked() {
+                            self.show_similarity = true;
+                            ui.close_menu();
+                        }
+                        if ui.button("Consensus").clicked() {
+                            self.build_consensus();
+                            self.show_consensus = true;
+                            ui.close_menu();
+                        }
+                        if ui.button("Outliers").clicked() {
+                            self.show_outliers = true;
+                            ui.close_menu();
+                        }
+                        if ui.button("Groups").clicked() {
+                            self.show_groups = true;
+                            ui.close_menu();
+                        }
+                        if ui.button("Trend").clicked() {
+                            self.show_trend = true;
+                            ui.close_menu();
+                        }
+                        if ui.button("Correlation").clicked() {
+                            self.show_correlation = true;
+                            ui.close_menu();
+                        }
+                        if ui.button("Bit Heatmap").clicked() {
+                            self.show_bit_heatmap = true;
+                            ui.close_menu();
                         }
+                        if ui.button("Shift Detection").clicked() {
+                            self.show_shift = true;
+                            ui.close_menu();
+                        }
+                        if ui.button("Patch").clicked() {
+                            self.show_patch = true;
+                            ui.close_menu();
+                        }
+                        if ui.button("Checksum").clicked() {
+                            self.show_checksum = true;
+                            ui.close_menu();
+                        }
+                        if ui.button("Rules").clicked() {
+                            self.show_rules = true;
+                            ui.close_menu();
+                        }
+                    });
+
+                    if ui.button("Export").clicked() {
+                        self.export_options.base = self.display_base;
+                        self.show_export = true;
+                    }
+
+                    if ui.button("Report").on_hover_text("Write a self-contained HTML comparison report").clicked() {
+                        self.generate_report();
                     }
                 });
 
@@ -488,9 +797,85 @@ impl eframe::App for AppState {
                     ui.label("Base:");
                     
                     ui.checkbox(&mut self.show_diff_column, "Show Diff");
+                    ui.checkbox(&mut self.unique_variants, "Unique Variants");
+
+                    // Derived view: baseline picker, then the view mode
+                    if self.value_view != derived::ValueView::Raw && !self.files.is_empty() {
+                        egui::ComboBox::from_id_source("baseline_file")
+                            .selected_text(self.files[self.baseline_file].file_name.as_str())
+                            .show_ui(ui, |ui| {
+                                for (idx, pf) in self.files.iter().enumerate() {
+                                    ui.selectable_value(&mut self.baseline_file, idx, &pf.file_name);
+                                }
+                            });
+                        ui.label("vs");
+                    }
+                    egui::ComboBox::from_id_source("value_view").selected_text(self.value_view.label()).show_ui(ui, |ui| {
+                        for view in derived::ValueView::ALL {
+                            ui.selectable_value(&mut self.value_view, view, view.label());
+                        }
+                    });
+                    ui.label("Values:");
                 });
             });
+
+            // Filter bar: applied on Enter or Apply, empty text shows every row
+            ui.horizontal(|ui| {
+                ui.label("Filter:");
+                let resp = ui.add(
+                    egui::TextEdit::singleline(&mut self.filter_text)
+                        .hint_text("addr >= 0x100 && addr < 0x200 && distinct > 1")
+                        .desired_width(420.0),
+                );
+                if resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    self.apply_filter();
+                }
+                if ui.button("Apply").clicked() {
+                    self.apply_filter();
+                }
+                if ui.button("Reset").clicked() {
+                    self.filter_text.clear();
+                    self.apply_filter();
+                }
+                if let Some(err) = &self.filter_error {
+                    ui.colored_label(egui::Color32::RED, err);
+                } else if self.filter.is_some() {
+                    ui.label(format!("{} of {} rows", self.visible_rows.len(), self.intersect_addresses.len()));
+                }
+            })
+            .response
+            .on_hover_text("Fields: addr, distinct, diff, value, value[N], value[\"name\"]\nOperators: == != < <= > >= & && || !");
+        });
+
+        // Ctrl+F opens search, F3 / Shift+F3 walk through the hits
+        let (open_search, step) = ctx.input(|i| {
+            let step = i.key_pressed(egui::Key::F3).then_some(!i.modifiers.shift);
+            (i.modifiers.command && i.key_pressed(egui::Key::F), step)
         });
+        if open_search {
+            self.show_search = true;
+        }
+        // Ctrl+Z / Ctrl+Y (or Ctrl+Shift+Z); text fields keep their own undo while focused
+        if !ctx.wants_keyboard_input() {
+            let (undo, redo) = ctx.input(|i| {
+                let z = i.modifiers.command && i.key_pressed(egui::Key::Z);
+                (z && !i.modifiers.shift, (z && i.modifiers.shift) || (i.modifiers.command && i.key_pressed(egui::Key::Y)))
+            });
+            if undo {
+                self.undo();
+            } else if redo {
+                self.redo();
+            }
+        }
+
+        self.refresh_visible_rows();
+        self.refresh_search_hits();
+        if let Some(forward) = step {
+            self.step_hit(forward);
+        }
+        self.refresh_consensus();
+        self.refresh_rule_report();
+        self.refresh_variants();
 
         egui::CentralPanel::default().show(ctx, |ui| {
             if self.files.is_empty() {
@@ -507,11 +892,30 @@ impl eframe::App for AppState {
             }
 
             // 构建列：1列地址 + (可选)1列差异 + N列数据，并支持水平滚动
+            let columns = self.table_columns();
+            if let Some(variants) = self.variants.as_ref().filter(|_| self.unique_variants) {
+                ui.label(format!("{} files in {} unique variants", self.files.len(), variants.list.len()));
+            }
+            let mut open_hexdiff = None;
+            let mut toggle_variant = None;
+            let mut save_file = None;
+            let mut begin_edit = None;
+            // Some(true) commits the edited cell, Some(false) cancels it
+            let mut finish_edit = None;
             egui::ScrollArea::horizontal().show(ui, |ui| {
                 let mut table = TableBuilder::new(ui).striped(true);
+                let baseline = self.files.get(self.baseline_file);
+                let scroll_row = self
+                    .scroll_to_addr
+                    .take()
+                    .and_then(|a| self.visible_rows.iter().position(|&i| self.intersect_addresses[i] == a));
+                if let Some(row) = scroll_row {
+                    table = table.scroll_to_row(row, Some(egui::Align::Center));
+                }
                 table = table.column(Column::initial(140.0).resizable(true)); // Address column
                 if self.show_diff_column { table = table.column(Column::initial(80.0).resizable(true)); } // Diff column
-                for _ in &self.files { table = table.column(Column::initial(120.0).resizable(true)); }
+                if self.consensus.is_some() { table = table.column(Column::initial(140.0).resizable(true)); } // Consensus column
+                for _ in &columns { table = table.column(Column::initial(120.0).resizable(true)); }
 
                 table
                     .header(24.0, |mut header| {
@@ -521,31 +925,82 @@ impl eframe::App for AppState {
                         if self.show_diff_column {
                             header.col(|ui| { ui.label("Diff"); });
                         }
+
+                        if self.consensus.is_some() {
+                            header.col(|ui| { ui.label("Consensus"); });
+                        }
                         
                         // File columns with delete button
-                        for (idx, pf) in self.files.iter().enumerate() {
+                        for column in &columns {
+                            let idx = column.file_idx;
+                            let pf = &self.files[idx];
                             header.col(|ui| { 
+                                // Collapsed variant: member count, members on hover, expand to drill down
+                                if column.collapsed() {
+                                    let (n, hash) = column.variant.unwrap_or_default();
+                                    ui.vertical(|ui| {
+                                        let members = column.members.iter().map(|&i| self.files[i].file_name.as_str()).collect::<Vec<_>>().join("\n");
+                                        ui.strong(format!("Variant {} ×{}", n, column.members.len())).on_hover_text(members);
+                                        ui.small(&pf.file_name);
+                                        if ui.button("Expand").clicked() {
+                                            toggle_variant = Some(hash);
+                                        }
+                                    });
+                                    return;
+                                }
                                 // Use vertical layout to stack label and delete button
                                 ui.vertical(|ui| {
-                                    // File name label that can wrap - use Label with wrap enabled
-                                    ui.add(egui::Label::new(&pf.file_name).wrap(true));
-                                    
-                                    // Delete button below the label
-                                    if ui.button("🗑️").clicked() {
-                                        self.files_to_remove.push(idx);
+                                    // File name label that can wrap - use Label with wrap enabled; * marks unsaved edits
+                                    let name = if pf.edited.is_empty() { pf.file_name.clone() } else { format!("{} *", pf.file_name) };
+                                    ui.add(egui::Label::new(name).wrap(true));
+                                    if !pf.group.is_empty() {
+                                        ui.small(format!("[{}]", pf.group));
+                                    }
+                                    if self.value_view != derived::ValueView::Raw && idx == self.baseline_file {
+                                        ui.small("(baseline)");
                                     }
+                                    
+                                    // Delete and hex diff buttons below the label
+                                    ui.horizontal(|ui| {
+                                        if ui.button("🗑️").clicked() {
+                                            self.files_to_remove.push(idx);
+                                        }
+                                        if ui.add_enabled(self.files.len() > 1, egui::Button::new("Hex")).on_hover_text("Hex diff against another file").clicked() {
+                                            open_hexdiff = Some(idx);
+                                        }
+                                        if !pf.edited.is_empty() && ui.button("💾").on_hover_text("Save edits back to a suffix-code file").clicked() {
+                                            save_file = Some(idx);
+                                        }
+                                        if let Some((n, hash)) = column.variant
+                                            && ui.button("Collapse").on_hover_text(format!("Merge variant {} back into one column", n)).clicked()
+                                        {
+                                            toggle_variant = Some(hash);
+                                        }
+                                    });
                                 });
                             });
                         }
                     })
                     .body(|mut body| {
-                        let mut row_idx: usize = 0;
-                        for addr in &self.intersect_addresses {
+                        for &row_idx in &self.visible_rows {
+                            let addr = &self.intersect_addresses[row_idx];
                             body.row(22.0, |mut row| {
                                 // Address column (click to select row)
                                 row.col(|ui| {
                                     let is_selected = self.selected_row == Some(row_idx);
-                                    let resp = ui.add(egui::SelectableLabel::new(is_selected, format_addr(*addr)));
+                                    let mut resp = ui.add(egui::SelectableLabel::new(is_selected, format_addr(*addr)));
+                                    // Original addresses of files that were rebased
+                                    if self.files.iter().any(|pf| pf.offset != 0) {
+                                        let lines: Vec<String> = self
+                                            .files
+                                            .iter()
+                                            .map(|pf| {
+                                                let original = pf.original_addr(*addr).map(format_addr).unwrap_or_default();
+                                                format!("{}: {}", pf.file_name, original)
+                                            })
+                                            .collect();
+                                        resp = resp.on_hover_text(format!("Original addresses:\n{}", lines.join("\n")));
+                                    }
                                     if resp.clicked() {
                                         self.selected_row = Some(row_idx);
                                     }
@@ -563,20 +1018,81 @@ impl eframe::App for AppState {
                                         ui.colored_label(color, text);
                                     });
                                 }
+
+                                // Consensus column
+                                if let Some(consensus) = &self.consensus {
+                                    row.col(|ui| {
+                                        if let Some(entry) = consensus.entries.get(addr) {
+                                            entry.cell_ui(ui);
+                                        }
+                                    });
+                                }
                                 
-                                // Data columns
-                                for pf in &self.files {
+                                // Data columns (search hits and cells failing a loaded rule are highlighted)
+                                for column in &columns {
+                                    let (file_idx, pf) = (column.file_idx, &self.files[column.file_idx]);
                                     row.col(|ui| {
-                                        let raw = pf.address_to_data.get(addr).cloned().unwrap_or_default();
-                                        ui.monospace(format_data_with_base(&raw, self.display_base));
+                                        // Cell editor: Enter commits, Escape or clicking elsewhere cancels
+                                        if let Some(edit) = self.editing.as_mut().filter(|e| e.file_idx == file_idx && e.addr == *addr && !column.collapsed()) {
+                                            let mut resp = ui.add(egui::TextEdit::singleline(&mut edit.text).font(egui::TextStyle::Monospace).desired_width(f32::INFINITY));
+                                            if let Some(err) = &edit.error {
+                                                resp = resp.on_hover_text(egui::RichText::new(err).color(egui::Color32::RED));
+                                                ui.painter().rect_stroke(ui.max_rect(), 0.0, egui::Stroke::new(1.5, egui::Color32::RED));
+                                            }
+                                            if resp.lost_focus() {
+                                                finish_edit = Some(ui.input(|i| i.key_pressed(egui::Key::Enter)));
+                                            } else {
+                                                resp.request_focus();
+                                            }
+                                            return;
+                                        }
+                                        // Zero XOR / delta values are dimmed
+                                        let (text, zero) = derived::cell_value(pf, baseline, *addr, self.value_view, self.display_base);
+                                        let mut text = egui::RichText::new(text).monospace();
+                                        if zero {
+                                            text = text.color(egui::Color32::LIGHT_GRAY);
+                                        }
+                                        if let Some(current) = self.search.hit_at(*addr, file_idx) {
+                                            let color = if current { egui::Color32::from_rgb(255, 170, 60) } else { egui::Color32::from_rgb(255, 240, 150) };
+                                            ui.painter().rect_filled(ui.max_rect(), 0.0, color);
+                                        }
+                                        if pf.is_edited(*addr) {
+                                            ui.painter().rect_filled(ui.max_rect(), 0.0, egui::Color32::from_rgb(200, 225, 255));
+                                        }
+                                        let resp = if self.rule_report.as_ref().is_some_and(|r| r.failed(file_idx, *addr)) {
+                                            ui.painter().rect_filled(ui.max_rect(), 0.0, egui::Color32::from_rgb(255, 200, 200));
+                                            ui.add(egui::Label::new(text).sense(egui::Sense::click())).on_hover_text("Fails a loaded rule")
+                                        } else {
+                                            ui.add(egui::Label::new(text).sense(egui::Sense::click()))
+                                        };
+                                        if resp.double_clicked() && self.value_view == derived::ValueView::Raw && !column.collapsed() {
+                                            begin_edit = Some((file_idx, *addr));
+                                        }
                                     });
                                 }
                             });
-                            row_idx += 1;
                         }
                     });
             });
 
+            if let Some(idx) = open_hexdiff {
+                self.open_hexdiff(idx);
+            }
+            if let Some(hash) = toggle_variant {
+                self.toggle_variant(hash);
+            }
+            match finish_edit {
+                Some(true) => self.commit_edit(),
+                Some(false) => self.editing = None,
+                None => {}
+            }
+            if let Some((file_idx, addr)) = begin_edit {
+                self.begin_edit(file_idx, addr);
+            }
+            if let Some(idx) = save_file {
+                self.save_file(idx);
+            }
+
             // Note: multi-cell selection and copy features were removed per request.
         });
 
@@ -593,49 +1109,122 @@ impl eframe::App for AppState {
         });
 
         // Handle drag-and-drop files (acts like Add)
-        // Accepts .txt file paths; other cases are ignored with a warning
+        // Accepts .txt, .csv and .tsv file paths; other cases are ignored with a warning
         let dropped = ctx.input(|i| i.raw.dropped_files.clone());
         if !dropped.is_empty() {
+            let start = self.files.len();
             let mut added_any = false;
             for f in dropped {
                 if let Some(path) = f.path {
                     if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
-                        if ext.eq_ignore_ascii_case("txt") {
-                            match parse_txt_file(path.to_string_lossy().as_ref()) {
-                                Ok(pf) => {
-                                    info!("Parsed (drop): {} ({} rows)", pf.file_name, pf.address_to_data.len());
-                                    self.files.push(pf);
-                                    added_any = true;
+                        if import::EXTENSIONS.iter().any(|e| ext.eq_ignore_ascii_case(e)) {
+                            match import::load_path(&path) {
+                                Ok(parsed) => {
+                                    for pf in parsed {
+                                        info!("Parsed (drop): {} ({} rows)", pf.file_name, pf.address_to_data.len());
+                                        self.files.push(pf);
+                                        added_any = true;
+                                    }
                                 }
                                 Err(e) => {
                                     error!("Parse failed (drop): {:?}", e);
                                 }
                             }
                         } else {
-                            warn!("Ignored dropped file (not .txt): {}", path.to_string_lossy());
+                            warn!("Ignored dropped file (not .txt, .csv or .tsv): {}", path.to_string_lossy());
                         }
                     }
                 } else {
                     warn!("Dropped data without a path is not supported");
                 }
             }
-            if added_any { self.recalc_intersection(); }
+            if added_any {
+                self.record_added(start);
+                self.recalc_intersection();
+            }
         }
 
         // Process file removals
         self.remove_files();
 
+        if self.show_similarity {
+            self.show_similarity_window(ctx);
+        }
+        if self.pair_diff.is_some() {
+            self.show_pair_diff_window(ctx);
+        }
+        if self.hexdiff.is_some() {
+            self.show_hexdiff_window(ctx);
+        }
+        if self.show_consensus {
+            self.show_consensus_window(ctx);
+        }
+        if self.show_outliers {
+            self.show_outliers_window(ctx);
+        }
+        if self.show_groups {
+            self.show_groups_window(ctx);
+        }
+        if self.show_trend {
+            self.show_trend_window(ctx);
+        }
+        if self.show_correlation {
+            self.show_correlation_window(ctx);
+        }
+        if self.show_bit_heatmap {
+            self.show_bit_heatmap_window(ctx);
+        }
+        if self.show_checksum {
+            self.show_checksum_window(ctx);
+        }
+        if self.show_rules {
+            self.show_rules_window(ctx);
+        }
+        if self.show_shift {
+            self.show_shift_window(ctx);
+        }
+        if self.show_patch {
+            self.show_patch_window(ctx);
+        }
+        if self.show_layout {
+            self.show_layout_window(ctx);
+        }
+        if self.show_search {
+            self.show_search_window(ctx);
+        }
+        if self.show_export {
+            self.show_export_window(ctx);
+        }
+        if self.scroll_to_addr.is_some() {
+            ctx.request_repaint();
+        }
+        self.track_settings();
+
         if self.show_stats {
             let main_rect = ctx.input(|i| i.screen_rect());
+            let mut open = self.show_stats;
             egui::Window::new("Statistics")
                 .constrain_to(main_rect)
                 .max_size(main_rect.size())
-                .open(&mut self.show_stats)
+                .open(&mut open)
                 .show(ctx, |ui| {
                 ui.label(format!("Files: {}", self.files.len()));
                 let total_rows: usize = self.files.iter().map(|f| f.address_to_data.len()).sum();
                 ui.label(format!("Total rows: {}", total_rows));
                 ui.label(format!("Total addresses: {}", self.intersect_addresses.len()));
+                if self.filter.is_some() {
+                    ui.label(format!("Filtered addresses: {}", self.visible_rows.len()));
+                }
+
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.stats_tab, StatsTab::Selected, "Selected Address");
+                    ui.selectable_value(&mut self.stats_tab, StatsTab::Overview, "Overview");
+                });
+                if self.stats_tab == StatsTab::Overview {
+                    ui.separator();
+                    self.stats_overview_ui(ui);
+                    return;
+                }
 
                 // Toggle Percent/Count, Pie chart, and set color alpha & font scale
                 ui.horizontal(|ui| {
@@ -653,120 +1242,33 @@ impl eframe::App for AppState {
                         ui.separator();
                         ui.label(format!("Selected Address: {}", format_addr(addr)));
 
-                        // Group by data value: value_string -> Vec<file_name>
-                        use std::collections::BTreeMap;
-                        let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
-                        for pf in &self.files {
-                            let raw = pf.address_to_data.get(&addr).cloned().unwrap_or_default();
-                            let shown = format_data_with_base(&raw, self.display_base);
-                            groups.entry(shown).or_default().push(pf.file_name.clone());
-                        }
+                        // Group by data value: value_string -> Vec<file index>
+                        let groups = group_files_by_value(&self.files, addr, self.display_base);
                         let total = self.files.len() as f32;
                         let mut group_entries: Vec<(String, usize)> = groups.iter().map(|(k, v)| (k.clone(), v.len())).collect();
                         // Sort by count desc then key asc for stable display
                         group_entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
 
                         // Draw charts side-by-side (bars by count, pie by percentage)
+                        let colors = chart::chart_colors(group_entries.len(), self.chart_alpha);
                         ui.horizontal(|ui| {
                             // Calculate bar chart width based on pie chart visibility
                             let bar_width = if self.show_pie_chart { 360.0 } else { ui.available_width() };
-                            let desired = egui::vec2(bar_width, 220.0);
-                            let (rect, _resp) = ui.allocate_exact_size(desired, egui::Sense::hover());
-                            let mut shapes = Vec::new();
-                            let max_count = group_entries.iter().map(|(_, c)| *c as f32).fold(0.0, f32::max).max(1.0);
-                            let bar_gap = 6.0;
-                            let bar_count = group_entries.len() as f32;
-                            let bar_width = ((rect.width() - bar_gap * (bar_count + 1.0)) / bar_count).max(2.0);
-                            let mut colors = generate_palette(group_entries.len());
-                            // apply alpha to colors
-                            let a = (255.0 * self.chart_alpha) as u8;
-                            for c in &mut colors { *c = egui::Color32::from_rgba_unmultiplied(c.r(), c.g(), c.b(), a); }
-                            for (idx, (value_label, count)) in group_entries.iter().enumerate() {
-                                let h = (*count as f32 / max_count) * (rect.height() - 28.0);
-                                let left = rect.left() + bar_gap + (bar_width + bar_gap) * idx as f32;
-                                let r = egui::Rect::from_min_size(
-                                    egui::pos2(left, rect.bottom() - h - 4.0),
-                                    egui::vec2(bar_width, h),
-                                );
-                                let color = colors[idx % colors.len()];
-                                shapes.push(egui::Shape::rect_filled(r, 2.0, color));
-                                // label under bar (black text)
-                                let galley = ui.painter().layout(
-                                    value_label.clone(),
-                                    egui::FontId::monospace(10.0),
-                                    egui::Color32::BLACK,
-                                    rect.width(),
-                                );
-                                let text_pos = egui::pos2(left, rect.bottom() - 14.0);
-                                shapes.push(egui::Shape::galley(text_pos, galley, egui::Color32::BLACK));
-
-                                // percentage/count above bar (black text)
-                                let pct = (*count as f32 / total) * 100.0;
-                                let label_text = match self.stats_metric { StatsMetric::Percent => format!("{:.1}%", pct), StatsMetric::Count => format!("{}", count) };
-                                let galley_pct = ui.painter().layout(
-                                    label_text,
-                                    egui::FontId::monospace(10.0),
-                                    egui::Color32::BLACK,
-                                    rect.width(),
-                                );
-                                let pct_pos = egui::pos2(left, (r.top() - 12.0).max(rect.top() + 2.0));
-                                shapes.push(egui::Shape::galley(pct_pos, galley_pct, egui::Color32::BLACK));
-                            }
-                            ui.painter().extend(shapes);
+                            let (rect, _resp) = ui.allocate_exact_size(egui::vec2(bar_width, 220.0), egui::Sense::hover());
+                            chart::paint(ui.painter(), rect.min, chart::bar_chart(&group_entries, total, self.stats_metric, &colors, rect.size()));
 
                             // Pie chart (only if enabled)
                             if self.show_pie_chart {
-                                let desired2 = egui::vec2(220.0, 220.0);
-                                let (rect2, _resp2) = ui.allocate_exact_size(desired2, egui::Sense::hover());
-                            let center = rect2.center();
-                            let radius = rect2.size().min_elem() * 0.45;
-                            let mut start_angle: f32 = 0.0;
-                            let mut shapes2: Vec<egui::Shape> = Vec::new();
-                            if group_entries.len() == 1 {
-                                let base = colors[0 % colors.len()];
-                                let color = egui::Color32::from_rgba_unmultiplied(base.r(), base.g(), base.b(), a);
-                                shapes2.push(egui::Shape::circle_filled(center, radius, color));
-                                let (_value_label, count) = &group_entries[0];
-                                let pct = (*count as f32 / total) * 100.0;
-                                let text = format!("{:.1}%", pct);
-                                let galley_lbl = ui.painter().layout(
-                                    text,
-                                    egui::FontId::monospace(10.0),
-                                    egui::Color32::BLACK,
-                                    rect2.width(),
-                                );
-                                shapes2.push(egui::Shape::galley(center, galley_lbl, egui::Color32::BLACK));
-                            } else {
-                                for (i, (_value_label, count)) in group_entries.iter().enumerate() {
-                                    let frac = (*count as f32 / total).max(0.0) as f32;
-                                    let end_angle = start_angle + (frac * std::f32::consts::TAU).min(std::f32::consts::TAU - 1e-3);
-                                    let base = colors[i % colors.len()];
-                                    let color = egui::Color32::from_rgba_unmultiplied(base.r(), base.g(), base.b(), a);
-                                    shapes2.push(egui::Shape::convex_polygon(
-                                        pie_slice(center, radius, start_angle, end_angle),
-                                        color,
-                                        egui::Stroke::NONE,
-                                    ));
-
-                                    // label on slice: value and percentage (black text)
-                                    let mid = (start_angle + end_angle) * 0.5;
-                                    let label_pos = egui::pos2(
-                                        center.x + mid.cos() * radius * 0.65,
-                                        center.y + mid.sin() * radius * 0.65,
-                                    );
-                                    let pct = (*count as f32 / total) * 100.0;
-                                    let text = format!("{:.1}%", pct);
-                                    let galley_lbl = ui.painter().layout(
-                                        text,
-                                        egui::FontId::monospace(10.0),
-                                        egui::Color32::BLACK,
-                                        rect2.width(),
-                                    );
-                                    shapes2.push(egui::Shape::galley(label_pos, galley_lbl, egui::Color32::BLACK));
-                                    start_angle = end_angle;
-                                }
+                                let (rect2, _resp2) = ui.allocate_exact_size(egui::vec2(220.0, 220.0), egui::Sense::hover());
+                                chart::paint(ui.painter(), rect2.min, chart::pie_chart(&group_entries, total, &colors, rect2.size()));
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            if ui.button("Save SVG").clicked() {
+                                self.save_stats_chart(addr, false);
                             }
-                            ui.painter().extend(shapes2);
+                            if ui.button("Save PNG").clicked() {
+                                self.save_stats_chart(addr, true);
                             }
                         });
 
@@ -775,22 +1277,16 @@ impl eframe::App for AppState {
                         egui::Frame::none().show(ui, |ui| {
                             ui.set_min_height(140.0);
                             egui::ScrollArea::vertical().auto_shrink([false; 2]).show(ui, |ui| {
-                                let mut colors = generate_palette(group_entries.len());
-                                let a = (255.0 * self.chart_alpha) as u8;
-                                for c in &mut colors { *c = egui::Color32::from_rgba_unmultiplied(c.r(), c.g(), c.b(), a); }
                                 for (idx, (value_label, count)) in group_entries.iter().enumerate() {
                                     let files = groups.get(value_label);
-                                    let metric_text = match self.stats_metric {
-                                        StatsMetric::Percent => format!("{:.1}%", (*count as f32 / total) * 100.0),
-                                        StatsMetric::Count => format!("{}", count),
-                                    };
+                                    let metric_text = chart::metric_text(*count, total, self.stats_metric);
                                     let color = colors[idx % colors.len()];
                                     ui.horizontal_wrapped(|ui| {
                                         ui.label(egui::RichText::new(format!("Value {} ", value_label)).monospace().strong());
                                         ui.label(egui::RichText::new(format!("({})", metric_text)).color(color).monospace().strong());
                                         if let Some(files) = files {
                                             ui.label(": ");
-                                            let file_text = files.join(", ");
+                                            let file_text = files.iter().map(|&i| self.files[i].file_name.as_str()).collect::<Vec<_>>().join(", ");
                                             ui.add(egui::Label::new(egui::RichText::new(file_text).monospace()).wrap(true));
                                         }
                                     });
@@ -803,6 +1299,23 @@ impl eframe::App for AppState {
                     ui.label("Tip: click a row (Address column) to select.");
                 }
             });
+            self.show_stats = open;
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reports_duplicate_addresses() {
+        let path = std::env::temp_dir().join(format!("suffixcode_dup_{}.txt", std::process::id()));
+        fs::write(&path, "Tester A\n0001\t0\t02\t02\t02h\t7E\t126\t\n0002\t0\t02\t02\t02h\t7F\t127\t\nbad\n").unwrap();
+        let pf = parse_txt_file(path.to_str().unwrap()).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(pf.original_data[&0x02], "7F");
+        assert_eq!(pf.line_of[&0x02], 2);
+        assert_eq!(pf.diagnostics, ["line 3: duplicate address 0x02 (first on line 2), the later value is used", "line 4: fewer than 3 columns: bad"]);
+    }
+}
@@ -0,0 +1,189 @@
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use log::{error, info};
+use std::fs;
+
+use crate::consensus::{ConsensusStatus, consensus_at};
+use crate::{AppState, DisplayBase, ParsedFile, SortState, csv_field, format_addr, format_data_with_base, sort_header};
+
+pub(crate) struct OutlierRow {
+    pub(crate) file_idx: usize,
+    // Addresses where this file differs from the majority value
+    pub(crate) addresses: Vec<u64>,
+    // Addresses that had a single winning value (ties are skipped)
+    pub(crate) compared: usize,
+}
+
+impl OutlierRow {
+    pub(crate) fn percent(&self) -> f32 {
+        if self.compared == 0 { 0.0 } else { self.addresses.len() as f32 / self.compared as f32 * 100.0 }
+    }
+}
+
+pub(crate) struct OutlierReport {
+    revision: u64,
+    display_base: DisplayBase,
+    pub(crate) rows: Vec<OutlierRow>,
+    pub(crate) ties: usize,
+}
+
+impl OutlierReport {
+    pub(crate) fn compute(files: &[ParsedFile], addrs: &[u64], display_base: DisplayBase, revision: u64) -> Self {
+        let mut rows: Vec<OutlierRow> = (0..files.len())
+            .map(|file_idx| OutlierRow { file_idx, addresses: Vec::new(), compared: 0 })
+            .collect();
+        let mut ties = 0;
+        for &addr in addrs {
+            let Some(entry) = consensus_at(files, addr, display_base) else { continue };
+            if entry.status == ConsensusStatus::Tie {
+                ties += 1;
+                continue;
+            }
+            for (pf, row) in files.iter().zip(rows.iter_mut()) {
+                row.compared += 1;
+                let raw = pf.address_to_data.get(&addr).cloned().unwrap_or_default();
+                if format_data_with_base(&raw, display_base) != entry.value {
+                    row.addresses.push(addr);
+                }
+            }
+        }
+        Self { revision, display_base, rows, ties }
+    }
+
+    // Rows ordered by the given sort state (0 = index, 1 = file, 2 = disagreements, 3 = percent)
+    fn sorted(&self, files: &[ParsedFile], sort: SortState) -> Vec<&OutlierRow> {
+        let mut rows: Vec<&OutlierRow> = self.rows.iter().collect();
+        rows.sort_by(|a, b| {
+            let ord = match sort.column {
+                1 => files[a.file_idx].file_name.cmp(&files[b.file_idx].file_name),
+                2 | 3 => a.addresses.len().cmp(&b.addresses.len()),
+                _ => a.file_idx.cmp(&b.file_idx),
+            };
+            let ord = ord.then_with(|| a.file_idx.cmp(&b.file_idx));
+            if sort.descending { ord.reverse() } else { ord }
+        });
+        rows
+    }
+
+    fn to_csv(&self, files: &[ParsedFile], sort: SortState) -> String {
+        let mut csv = String::from("file,disagreements,percent,addresses\n");
+        for row in self.sorted(files, sort) {
+            let addrs = row.addresses.iter().map(|a| format_addr(*a)).collect::<Vec<_>>().join(" ");
+            csv.push_str(&format!(
+                "{},{},{:.1}%,{}\n",
+                csv_field(&files[row.file_idx].file_name),
+                row.addresses.len(),
+                row.percent(),
+                addrs
+            ));
+        }
+        csv
+    }
+}
+
+impl AppState {
+    pub(crate) fn show_outliers_window(&mut self, ctx: &egui::Context) {
+        let main_rect = ctx.input(|i| i.screen_rect());
+        let mut open = self.show_outliers;
+        egui::Window::new("Outliers")
+            .constrain_to(main_rect)
+            .max_size(main_rect.size())
+            .open(&mut open)
+            .show(ctx, |ui| self.outliers_ui(ui));
+        self.show_outliers = open;
+    }
+
+    fn outliers_ui(&mut self, ui: &mut egui::Ui) {
+        if self.files.len() < 2 {
+            ui.label("Load at least two files to rank outliers.");
+            return;
+        }
+        let stale = self
+            .outliers
+            .as_ref()
+            .is_none_or(|r| r.revision != self.data_revision || r.display_base != self.display_base);
+        if stale {
+            self.outliers = Some(OutlierReport::compute(&self.files, &self.intersect_addresses, self.display_base, self.data_revision));
+        }
+        let Some(report) = &self.outliers else { return };
+
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "Files: {}  |  Addresses: {}  |  Ties skipped: {}",
+                self.files.len(),
+                self.intersect_addresses.len(),
+                report.ties
+            ));
+            if ui.button("Export CSV").clicked()
+                && let Some(path) = rfd::FileDialog::new().set_file_name("outliers.csv").save_file()
+            {
+                if let Err(e) = fs::write(&path, report.to_csv(&self.files, self.outlier_sort)) {
+                    error!("Export failed: {:?}", e);
+                } else {
+                    info!("Exported: {}", path.to_string_lossy());
+                }
+            }
+        });
+        ui.separator();
+
+        let rows = report.sorted(&self.files, self.outlier_sort);
+        let mut sort = self.outlier_sort;
+        TableBuilder::new(ui)
+            .striped(true)
+            .column(Column::initial(50.0).resizable(true))
+            .column(Column::initial(200.0).resizable(true))
+            .column(Column::initial(110.0).resizable(true))
+            .column(Column::initial(80.0).resizable(true))
+            .column(Column::remainder())
+            .header(24.0, |mut header| {
+                header.col(|ui| sort_header(ui, "#", 0, &mut sort));
+                header.col(|ui| sort_header(ui, "File", 1, &mut sort));
+                header.col(|ui| sort_header(ui, "Disagreements", 2, &mut sort));
+                header.col(|ui| sort_header(ui, "%", 3, &mut sort));
+                header.col(|ui| { ui.label("Addresses"); });
+            })
+            .body(|body| {
+                body.rows(22.0, rows.len(), |mut row| {
+                    let r = rows[row.index()];
+                    row.col(|ui| { ui.label(r.file_idx.to_string()); });
+                    row.col(|ui| { ui.label(&self.files[r.file_idx].file_name); });
+                    row.col(|ui| { ui.label(r.addresses.len().to_string()); });
+                    row.col(|ui| { ui.label(format!("{:.1}%", r.percent())); });
+                    row.col(|ui| {
+                        let all = r.addresses.iter().map(|a| format_addr(*a)).collect::<Vec<_>>().join(", ");
+                        let shown: String = r.addresses.iter().take(8).map(|a| format_addr(*a)).collect::<Vec<_>>().join(", ");
+                        let text = if r.addresses.len() > 8 { format!("{} …", shown) } else { shown };
+                        ui.monospace(text).on_hover_text(all);
+                    });
+                });
+            });
+        self.outlier_sort = sort;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsed_file;
+
+    #[test]
+    fn counts_disagreements_and_skips_ties() {
+        // 0x10: majority 01, 0x11: tie, 0x12: no majority (02 leads), file 2 lacks it
+        let files = [
+            parsed_file(&[(0x10, "01"), (0x11, "AA"), (0x12, "02")]),
+            parsed_file(&[(0x10, "0x01"), (0x11, "BB"), (0x12, "02")]),
+            parsed_file(&[(0x10, "FF"), (0x11, "AA")]),
+            parsed_file(&[(0x10, "01"), (0x11, "BB"), (0x12, "03")]),
+        ];
+        let report = OutlierReport::compute(&files, &[0x10, 0x11, 0x12], DisplayBase::Hex, 1);
+        assert_eq!(report.ties, 1);
+        let addresses: Vec<_> = report.rows.iter().map(|r| r.addresses.clone()).collect();
+        assert_eq!(addresses, [vec![], vec![], vec![0x10, 0x12], vec![0x12]]);
+        assert!(report.rows.iter().all(|r| r.compared == 2));
+        assert_eq!(report.rows[2].percent(), 100.0);
+        assert_eq!(report.rows[3].percent(), 50.0);
+
+        let worst: Vec<_> = report.sorted(&files, SortState { column: 2, descending: true }).iter().map(|r| r.file_idx).collect();
+        assert_eq!(worst, [2, 3, 1, 0]);
+    }
+}
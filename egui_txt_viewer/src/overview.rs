@@ -0,0 +1,220 @@
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use std::cmp::Ordering;
+
+use crate::search::parse_in_base;
+use crate::{AppState, DisplayBase, ParsedFile, SortState, format_addr, group_files_by_value, parse_data_value, sort_header};
+
+// Per-address summary across all loaded files
+pub(crate) struct AddressStats {
+    pub(crate) addr: u64,
+    pub(crate) distinct: usize,
+    pub(crate) majority: String,
+    pub(crate) majority_pct: f32,
+    // Shannon entropy of the value distribution, in bits
+    pub(crate) entropy: f64,
+    // Numeric summary over values that parse as numbers
+    pub(crate) min: Option<u64>,
+    pub(crate) max: Option<u64>,
+    pub(crate) mean: Option<f64>,
+    pub(crate) stddev: Option<f64>,
+}
+
+pub(crate) fn address_stats(files: &[ParsedFile], addr: u64, display_base: DisplayBase) -> AddressStats {
+    let groups = group_files_by_value(files, addr, display_base);
+    let total = files.len().max(1) as f64;
+    let (majority, votes) = groups
+        .iter()
+        .map(|(value, members)| (value.clone(), members.len()))
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+        .unwrap_or_default();
+    let entropy = groups
+        .values()
+        .map(|members| {
+            let p = members.len() as f64 / total;
+            -p * p.log2()
+        })
+        .sum::<f64>()
+        .max(0.0);
+
+    let values: Vec<u64> = files
+        .iter()
+        .filter_map(|pf| pf.address_to_data.get(&addr).and_then(|raw| parse_data_value(raw)))
+        .collect();
+    let (mean, stddev) = if values.is_empty() {
+        (None, None)
+    } else {
+        let n = values.len() as f64;
+        let mean = values.iter().map(|&v| v as f64).sum::<f64>() / n;
+        let var = values.iter().map(|&v| (v as f64 - mean).powi(2)).sum::<f64>() / n;
+        (Some(mean), Some(var.sqrt()))
+    };
+    AddressStats {
+        addr,
+        distinct: groups.len(),
+        majority,
+        majority_pct: (votes as f64 / total * 100.0) as f32,
+        entropy,
+        min: values.iter().copied().min(),
+        max: values.iter().copied().max(),
+        mean,
+        stddev,
+    }
+}
+
+pub(crate) struct StatsOverview {
+    revision: u64,
+    // Row filter the overview was computed under
+    filter_revision: u64,
+    display_base: DisplayBase,
+    sort: SortState,
+    pub(crate) rows: Vec<AddressStats>,
+}
+
+impl StatsOverview {
+    fn compute(files: &[ParsedFile], addrs: &[u64], display_base: DisplayBase, revision: u64, filter_revision: u64, sort: SortState) -> Self {
+        let rows = addrs.iter().map(|&addr| address_stats(files, addr, display_base)).collect();
+        let mut overview = Self { revision, filter_revision, display_base, sort, rows };
+        overview.sort_rows();
+        overview
+    }
+
+    // Columns: 0 address, 1 distinct, 2 majority, 3 majority %, 4 entropy, 5 min, 6 max, 7 mean, 8 stddev
+    fn sort_rows(&mut self) {
+        let sort = self.sort;
+        let base = self.display_base;
+        // Majority is shown in the display base; numbers sort by value, other text after them
+        let majority = |a: &AddressStats, b: &AddressStats| match (parse_in_base(&a.majority, base), parse_in_base(&b.majority, base)) {
+            (Some(x), Some(y)) => x.cmp(&y),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => a.majority.cmp(&b.majority),
+        };
+        let float = |a: f64, b: f64| a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+        let opt_float = |a: Option<f64>, b: Option<f64>| match (a, b) {
+            (Some(a), Some(b)) => float(a, b),
+            (a, b) => a.is_some().cmp(&b.is_some()),
+        };
+        self.rows.sort_by(|a, b| {
+            let ord = match sort.column {
+                1 => a.distinct.cmp(&b.distinct),
+                2 => majority(a, b),
+                3 => float(a.majority_pct as f64, b.majority_pct as f64),
+                4 => float(a.entropy, b.entropy),
+                5 => a.min.cmp(&b.min),
+                6 => a.max.cmp(&b.max),
+                7 => opt_float(a.mean, b.mean),
+                8 => opt_float(a.stddev, b.stddev),
+                _ => Ordering::Equal,
+            };
+            let ord = ord.then_with(|| a.addr.cmp(&b.addr));
+            if sort.descending { ord.reverse() } else { ord }
+        });
+    }
+}
+
+fn opt_text<T: std::fmt::Display>(v: Option<T>) -> String {
+    v.map_or_else(|| "-".to_string(), |v| v.to_string())
+}
+
+impl AppState {
+    pub(crate) fn stats_overview_ui(&mut self, ui: &mut egui::Ui) {
+        let stale = self
+            .stats_overview
+            .as_ref()
+            .is_none_or(|o| {
+                o.revision != self.data_revision || o.filter_revision != self.filter_revision || o.display_base != self.display_base
+            });
+        if stale {
+            self.stats_overview = Some(StatsOverview::compute(
+                &self.files,
+                &self.visible_addresses(),
+                self.display_base,
+                self.data_revision,
+                self.filter_revision,
+                self.overview_sort,
+            ));
+        }
+        let selected = self.selected_addr();
+        let Some(overview) = &mut self.stats_overview else { return };
+        if overview.sort != self.overview_sort {
+            overview.sort = self.overview_sort;
+            overview.sort_rows();
+        }
+
+        ui.label("Click a column header to sort, click an address to select it.");
+        let mut sort = self.overview_sort;
+        let mut select = None;
+        let headers = ["Address", "Distinct", "Majority", "Majority %", "Entropy", "Min", "Max", "Mean", "StdDev"];
+        let mut table = TableBuilder::new(ui).striped(true).max_scroll_height(420.0);
+        for _ in &headers {
+            table = table.column(Column::initial(90.0).resizable(true));
+        }
+        table
+            .header(24.0, |mut header| {
+                for (idx, label) in headers.iter().enumerate() {
+                    header.col(|ui| sort_header(ui, label, idx, &mut sort));
+                }
+            })
+            .body(|body| {
+                body.rows(20.0, overview.rows.len(), |mut row| {
+                    let s = &overview.rows[row.index()];
+                    row.col(|ui| {
+                        if ui.selectable_label(selected == Some(s.addr), format_addr(s.addr)).clicked() {
+                            select = Some(s.addr);
+                        }
+                    });
+                    row.col(|ui| { ui.label(s.distinct.to_string()); });
+                    row.col(|ui| { ui.monospace(&s.majority); });
+                    row.col(|ui| { ui.label(format!("{:.1}%", s.majority_pct)); });
+                    row.col(|ui| { ui.label(format!("{:.3}", s.entropy)); });
+                    row.col(|ui| { ui.label(opt_text(s.min)); });
+                    row.col(|ui| { ui.label(opt_text(s.max)); });
+                    row.col(|ui| { ui.label(opt_text(s.mean.map(|v| format!("{:.2}", v)))); });
+                    row.col(|ui| { ui.label(opt_text(s.stddev.map(|v| format!("{:.2}", v)))); });
+                });
+            });
+        self.overview_sort = sort;
+        if let Some(addr) = select {
+            self.select_addr(addr);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsed_file;
+
+    fn overview(rows: Vec<AddressStats>, display_base: DisplayBase, column: usize) -> Vec<u64> {
+        let sort = SortState { column, descending: false };
+        let mut overview = StatsOverview { revision: 0, filter_revision: 0, display_base, sort, rows };
+        overview.sort_rows();
+        overview.rows.iter().map(|r| r.addr).collect()
+    }
+
+    fn stats(addr: u64, majority: &str) -> AddressStats {
+        AddressStats { addr, distinct: 1, majority: majority.to_string(), majority_pct: 100.0, entropy: 0.0, min: None, max: None, mean: None, stddev: None }
+    }
+
+    #[test]
+    fn address_stats_summarise_the_values() {
+        let files = [parsed_file(&[(0, "10")]), parsed_file(&[(0, "10")]), parsed_file(&[(0, "20")]), parsed_file(&[(0, "zz")])];
+        let s = address_stats(&files, 0, DisplayBase::Hex);
+        assert_eq!((s.distinct, s.majority.as_str(), s.majority_pct), (3, "0x10", 50.0));
+        assert!((s.entropy - 1.5).abs() < 1e-9);
+        assert_eq!((s.min, s.max), (Some(0x10), Some(0x20)));
+        let mean = s.mean.unwrap();
+        assert!((mean - 0x40 as f64 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn majority_sorts_by_value_in_the_display_base() {
+        let rows = || vec![stats(1, "100"), stats(2, "20"), stats(3, "n/a"), stats(4, "3")];
+        assert_eq!(overview(rows(), DisplayBase::Dec, 2), [4, 2, 1, 3]);
+        let rows = vec![stats(1, "00000100"), stats(2, "00000011"), stats(3, "?"), stats(4, "00001000")];
+        assert_eq!(overview(rows, DisplayBase::Bin, 2), [2, 1, 4, 3]);
+        let rows = vec![stats(1, "0x100"), stats(2, "0x7E"), stats(3, "0xFF")];
+        assert_eq!(overview(rows, DisplayBase::Hex, 2), [2, 3, 1]);
+    }
+}
@@ -0,0 +1,321 @@
+use eframe::egui;
+use log::{error, info, warn};
+use std::fs;
+
+use crate::{AppState, ParsedFile, format_addr, parse_data_value, parse_user_number};
+
+// Machine-applicable format, one change per line after the header:
+//   #SCPATCH 1
+//   #from<TAB>a.txt
+//   #to<TAB>b.txt
+//   0x0010<TAB>7E<TAB>80      (changed)
+//   0x0020<TAB>-<TAB>05       (added in B)
+//   0x0030<TAB>11<TAB>-       (missing in B)
+const PATCH_MAGIC: &str = "#SCPATCH 1";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct PatchEntry {
+    pub(crate) addr: u64,
+    // None = address absent on that side
+    pub(crate) old: Option<String>,
+    pub(crate) new: Option<String>,
+}
+
+// Normalize a data cell to upper-case hex digits (non-numeric data is kept as written)
+fn normalize(raw: &str) -> String {
+    parse_data_value(raw).map_or_else(|| raw.trim().to_string(), |v| format!("{:02X}", v))
+}
+
+// Changes that turn a into b, over the union of both files' addresses
+pub(crate) fn diff_files(a: &ParsedFile, b: &ParsedFile) -> Vec<PatchEntry> {
+    let mut addrs: Vec<u64> = a.address_to_data.keys().chain(b.address_to_data.keys()).copied().collect();
+    addrs.sort_unstable();
+    addrs.dedup();
+    addrs
+        .into_iter()
+        .filter_map(|addr| {
+            let old = a.address_to_data.get(&addr).map(|r| normalize(r));
+            let new = b.address_to_data.get(&addr).map(|r| normalize(r));
+            (old != new).then_some(PatchEntry { addr, old, new })
+        })
+        .collect()
+}
+
+fn counts(entries: &[PatchEntry]) -> (usize, usize, usize) {
+    let added = entries.iter().filter(|e| e.old.is_none()).count();
+    let removed = entries.iter().filter(|e| e.new.is_none()).count();
+    (entries.len() - added - removed, added, removed)
+}
+
+fn readable_line(e: &PatchEntry) -> String {
+    let show = |v: &Option<String>| v.as_ref().map_or_else(|| "(none)".to_string(), |v| format!("0x{}", v));
+    format!("{}: {} → {}", format_addr(e.addr), show(&e.old), show(&e.new))
+}
+
+pub(crate) fn format_readable(entries: &[PatchEntry], from: &str, to: &str) -> String {
+    let (changed, added, removed) = counts(entries);
+    let mut out = format!("Patch: {} -> {}\n{} changed, {} added, {} removed\n\n", from, to, changed, added, removed);
+    for e in entries {
+        out.push_str(&readable_line(e));
+        out.push('\n');
+    }
+    out
+}
+
+pub(crate) fn format_machine(entries: &[PatchEntry], from: &str, to: &str) -> String {
+    let show = |v: &Option<String>| v.clone().unwrap_or_else(|| "-".to_string());
+    let mut out = format!("{}\n#from\t{}\n#to\t{}\n", PATCH_MAGIC, from, to);
+    for e in entries {
+        out.push_str(&format!("{}\t{}\t{}\n", format_addr(e.addr), show(&e.old), show(&e.new)));
+    }
+    out
+}
+
+pub(crate) fn parse_machine(text: &str) -> Result<Vec<PatchEntry>, String> {
+    let mut lines = text.lines().enumerate();
+    match lines.next() {
+        Some((_, first)) if first.trim() == PATCH_MAGIC => {}
+        _ => return Err(format!("not a patch file (missing '{}' header)", PATCH_MAGIC)),
+    }
+    let side = |v: &str| if v == "-" { None } else { Some(normalize(v)) };
+    let mut entries = Vec::new();
+    for (idx, line) in lines {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let parts: Vec<&str> = line.split('\t').map(str::trim).collect();
+        let [addr, old, new] = parts[..] else {
+            return Err(format!("line {}: expected address, old and new value", idx + 1));
+        };
+        let addr = parse_user_number(addr).ok_or_else(|| format!("line {}: invalid address '{}'", idx + 1, addr))?;
+        entries.push(PatchEntry { addr, old: side(old), new: side(new) });
+    }
+    Ok(entries)
+}
+
+// Result of applying a patch
+pub(crate) struct PatchOutcome {
+    pub(crate) file: ParsedFile,
+    // Applied entries whose old value did not match the target
+    pub(crate) mismatched: usize,
+    // Entries not applied: the address does not map back through the target's offset or lies
+    // outside its include-range
+    pub(crate) outside: usize,
+}
+
+pub(crate) fn apply_patch(base: &ParsedFile, entries: &[PatchEntry], patch_name: &str) -> PatchOutcome {
+    let mut pf = base.clone();
+    pf.file_name = format!("{} + {}", base.file_name, patch_name);
+    pf.path = None;
+    let (mut mismatched, mut outside) = (0, 0);
+    for e in entries {
+        let Some(original) = pf.original_addr(e.addr).filter(|a| pf.include_range.is_none_or(|(lo, hi)| (lo..=hi).contains(a))) else {
+            outside += 1;
+            continue;
+        };
+        if pf.address_to_data.get(&e.addr).map(|r| normalize(r)) != e.old {
+            mismatched += 1;
+        }
+        match &e.new {
+            Some(v) => {
+                pf.original_data.insert(original, v.clone());
+                pf.edited.insert(original);
+            }
+            None => {
+                // Marked as edited too, so saving drops the row from the source text
+                if pf.original_data.remove(&original).is_some() {
+                    pf.edited.insert(original);
+                }
+            }
+        }
+    }
+    pf.apply_layout();
+    PatchOutcome { file: pf, mismatched, outside }
+}
+
+// Diff between the From / To files, kept until the data or the selected pair changes
+struct PatchPreview {
+    revision: u64,
+    pair: (usize, usize),
+    entries: Vec<PatchEntry>,
+    // (changed, added, removed)
+    counts: (usize, usize, usize),
+}
+
+#[derive(Default)]
+pub(crate) struct PatchState {
+    from: usize,
+    to: usize,
+    target: usize,
+    message: Option<String>,
+    preview: Option<PatchPreview>,
+}
+
+impl AppState {
+    pub(crate) fn show_patch_window(&mut self, ctx: &egui::Context) {
+        let main_rect = ctx.input(|i| i.screen_rect());
+        let mut open = self.show_patch;
+        egui::Window::new("Patch")
+            .constrain_to(main_rect)
+            .max_size(main_rect.size())
+            .open(&mut open)
+            .show(ctx, |ui| self.patch_ui(ui));
+        self.show_patch = open;
+    }
+
+    fn patch_ui(&mut self, ui: &mut egui::Ui) {
+        if self.files.is_empty() {
+            ui.label("No files loaded.");
+            return;
+        }
+        let last = self.files.len() - 1;
+        let state = &mut self.patch;
+        state.from = state.from.min(last);
+        state.to = state.to.min(last);
+        state.target = state.target.min(last);
+
+        ui.heading("Create");
+        ui.horizontal(|ui| {
+            for (label, idx, id) in [("From (A):", &mut state.from, "patch_from"), ("To (B):", &mut state.to, "patch_to")] {
+                ui.label(label);
+                egui::ComboBox::from_id_source(id).selected_text(self.files[*idx].file_name.as_str()).show_ui(ui, |ui| {
+                    for (i, pf) in self.files.iter().enumerate() {
+                        ui.selectable_value(idx, i, &pf.file_name);
+                    }
+                });
+            }
+        });
+        let (fa, fb) = (&self.files[state.from], &self.files[state.to]);
+        let pair = (state.from, state.to);
+        if state.preview.as_ref().is_none_or(|p| p.revision != self.data_revision || p.pair != pair) {
+            let entries = diff_files(fa, fb);
+            let counts = counts(&entries);
+            state.preview = Some(PatchPreview { revision: self.data_revision, pair, entries, counts });
+        }
+        let Some(preview) = &state.preview else { return };
+        let entries = &preview.entries;
+        let (changed, added, removed) = preview.counts;
+        ui.label(format!("{} changed, {} added, {} removed", changed, added, removed));
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        egui::ScrollArea::vertical().id_source("patch_preview").max_height(200.0).show_rows(ui, row_height, entries.len(), |ui, range| {
+            for e in &entries[range] {
+                ui.monospace(readable_line(e));
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!entries.is_empty(), egui::Button::new("Save Readable")).clicked() {
+                save_text("patch.txt", &format_readable(entries, &fa.file_name, &fb.file_name));
+            }
+            if ui.add_enabled(!entries.is_empty(), egui::Button::new("Save Patch")).clicked() {
+                save_text("patch.scpatch", &format_machine(entries, &fa.file_name, &fb.file_name));
+            }
+        });
+
+        ui.separator();
+        ui.heading("Apply");
+        let mut apply = false;
+        ui.horizontal(|ui| {
+            ui.label("Target:");
+            egui::ComboBox::from_id_source("patch_target").selected_text(self.files[state.target].file_name.as_str()).show_ui(ui, |ui| {
+                for (i, pf) in self.files.iter().enumerate() {
+                    ui.selectable_value(&mut state.target, i, &pf.file_name);
+                }
+            });
+            apply = ui.button("Load Patch and Apply").clicked();
+        });
+        ui.label("The patched data is added as a new column; the target stays unchanged. Saving the new column writes added addresses as new rows and drops removed ones.");
+        if let Some(msg) = &state.message {
+            ui.label(msg);
+        }
+        if apply {
+            self.load_and_apply_patch();
+        }
+    }
+
+    fn load_and_apply_patch(&mut self) {
+        let Some(path) = rfd::FileDialog::new().add_filter("Patch", &["scpatch"]).pick_file() else { return };
+        let entries = match fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|text| parse_machine(&text)) {
+            Ok(entries) => entries,
+            Err(e) => {
+                error!("Patch load failed: {}", e);
+                self.patch.message = Some(format!("Load failed: {}", e));
+                return;
+            }
+        };
+        let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        let outcome = apply_patch(&self.files[self.patch.target], &entries, &name);
+        let applied = entries.len() - outcome.outside;
+        if outcome.mismatched > 0 {
+            warn!("{} of {} patch entries did not match the target's old value", outcome.mismatched, applied);
+        }
+        if outcome.outside > 0 {
+            warn!("{} patch entries lie outside the target's address layout and were not applied", outcome.outside);
+        }
+        info!("Applied {} ({} of {} entries) to {}", name, applied, entries.len(), self.files[self.patch.target].file_name);
+        let mut message = format!("Applied {} of {} entries, {} with a different old value.", applied, entries.len(), outcome.mismatched);
+        if outcome.outside > 0 {
+            message.push_str(&format!(" {} outside the target's offset / include-range were skipped.", outcome.outside));
+        }
+        self.patch.message = Some(message);
+        let start = self.files.len();
+        self.files.push(outcome.file);
+        self.record_added(start);
+        self.recalc_intersection();
+    }
+}
+
+fn save_text(default_name: &str, text: &str) {
+    if let Some(path) = rfd::FileDialog::new().set_file_name(default_name).save_file() {
+        if let Err(e) = fs::write(&path, text) {
+            error!("Export failed: {:?}", e);
+        } else {
+            info!("Exported: {}", path.to_string_lossy());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsed_file;
+
+    #[test]
+    fn apply_marks_changed_added_and_removed_addresses_for_saving() {
+        let a = parsed_file(&[(0x10, "7E"), (0x30, "11")]);
+        let b = parsed_file(&[(0x10, "80"), (0x20, "05")]);
+        let entries = diff_files(&a, &b);
+        assert_eq!(counts(&entries), (1, 1, 1));
+        assert_eq!(parse_machine(&format_machine(&entries, "a", "b")), Ok(entries.clone()));
+
+        let outcome = apply_patch(&a, &entries, "p");
+        assert_eq!((outcome.mismatched, outcome.outside), (0, 0));
+        assert_eq!(outcome.file.address_to_data, b.address_to_data);
+        assert_eq!(outcome.file.edited.iter().copied().collect::<Vec<_>>(), vec![0x10, 0x20, 0x30]);
+    }
+
+    #[test]
+    fn entries_outside_a_cropped_target_are_counted_not_applied() {
+        let mut target = parsed_file(&[(0x10, "7E"), (0x11, "01"), (0x40, "FF")]);
+        target.include_range = Some((0x10, 0x1F));
+        target.apply_layout();
+        let entries = vec![
+            PatchEntry { addr: 0x10, old: Some("7E".into()), new: Some("80".into()) },
+            PatchEntry { addr: 0x11, old: Some("02".into()), new: Some("03".into()) },
+            PatchEntry { addr: 0x20, old: None, new: Some("05".into()) },
+            PatchEntry { addr: 0x40, old: Some("FF".into()), new: None },
+        ];
+        let outcome = apply_patch(&target, &entries, "p");
+        assert_eq!((outcome.mismatched, outcome.outside), (1, 2));
+        assert_eq!(outcome.file.original_data[&0x40], "FF");
+        assert!(!outcome.file.original_data.contains_key(&0x20));
+        assert_eq!(outcome.file.edited.iter().copied().collect::<Vec<_>>(), vec![0x10, 0x11]);
+
+        // With an offset, an address below it does not map back at all
+        let mut shifted = parsed_file(&[(0x00, "01")]);
+        shifted.offset = 0x100;
+        shifted.apply_layout();
+        let outcome = apply_patch(&shifted, &[PatchEntry { addr: 0x10, old: None, new: Some("01".into()) }], "p");
+        assert_eq!(outcome.outside, 1);
+    }
+}
@@ -0,0 +1,245 @@
+use log::{error, info, warn};
+use std::collections::BTreeSet;
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::export::html_escape;
+use crate::outliers::OutlierReport;
+use crate::overview::address_stats;
+use crate::{AppState, format_addr, is_data_different};
+
+// Distribution charts are embedded for at most this many differing addresses
+const MAX_CHARTS: usize = 100;
+// Addresses listed per file in the outlier ranking
+const MAX_OUTLIER_ADDRS: usize = 16;
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 24px; color: #222; }
+table { border-collapse: collapse; margin-bottom: 16px; }
+th, td { border: 1px solid #ccc; padding: 2px 8px; text-align: left; vertical-align: top; }
+th { background: #eee; }
+td.num { text-align: right; font-family: monospace; }
+.mono { font-family: monospace; }
+.warn { color: #b00; }
+.chart { margin: 8px 0 24px 0; }
+";
+
+// Contiguous run of differing addresses (consecutive addresses, inclusive)
+struct DiffRegion {
+    start: u64,
+    end: u64,
+    count: usize,
+}
+
+fn diff_regions(addrs: &[u64]) -> Vec<DiffRegion> {
+    let mut regions: Vec<DiffRegion> = Vec::new();
+    for &addr in addrs {
+        match regions.last_mut() {
+            Some(r) if r.end.checked_add(1) == Some(addr) => {
+                r.end = addr;
+                r.count += 1;
+            }
+            _ => regions.push(DiffRegion { start: addr, end: addr, count: 1 }),
+        }
+    }
+    regions
+}
+
+// UTC "YYYY-MM-DD HH:MM:SS" without pulling in a date crate (days-to-civil conversion)
+fn format_time(t: SystemTime) -> String {
+    let Ok(d) = t.duration_since(UNIX_EPOCH) else { return "-".to_string() };
+    let secs = d.as_secs();
+    let (days, rem) = ((secs / 86400) as i64, secs % 86400);
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC", year, month, day, rem / 3600, rem % 3600 / 60, rem % 60)
+}
+
+fn percent(part: usize, whole: usize) -> f32 {
+    if whole == 0 { 0.0 } else { part as f32 / whole as f32 * 100.0 }
+}
+
+impl AppState {
+    fn report_files(&self, out: &mut String) {
+        out.push_str("<h2>Files</h2>\n<table>\n<tr><th>#</th><th>File</th><th>Path</th><th>Modified</th><th>Rows</th><th>Address range</th><th>Layout</th><th>Group</th><th>Header</th><th>Diagnostics</th></tr>\n");
+        for (idx, pf) in self.files.iter().enumerate() {
+            let range = match (pf.original_data.keys().next(), pf.original_data.keys().next_back()) {
+                (Some(lo), Some(hi)) => format!("{} – {}", format_addr(*lo), format_addr(*hi)),
+                _ => "-".to_string(),
+            };
+            let mut layout = Vec::new();
+            if pf.offset != 0 {
+                layout.push(format!("offset {:+}", pf.offset));
+            }
+            if let Some((lo, hi)) = pf.include_range {
+                layout.push(format!("only {} – {}", format_addr(lo), format_addr(hi)));
+            }
+            if !pf.edited.is_empty() {
+                layout.push(format!("{} unsaved edit(s)", pf.edited.len()));
+            }
+            let diagnostics = if pf.diagnostics.is_empty() {
+                "none".to_string()
+            } else {
+                let lines: Vec<String> = pf.diagnostics.iter().map(|d| html_escape(d)).collect();
+                format!("<details><summary class=\"warn\">{} diagnostic(s)</summary><div class=\"mono\">{}</div></details>", pf.diagnostics.len(), lines.join("<br>"))
+            };
+            out.push_str(&format!(
+                "<tr><td class=\"num\">{}</td><td>{}</td><td class=\"mono\">{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"mono\">{}</td><td>{}</td><td>{}</td><td class=\"mono\">{}</td><td>{}</td></tr>\n",
+                idx + 1,
+                html_escape(&pf.file_name),
+                pf.path.as_ref().map_or_else(|| "(not saved)".to_string(), |p| html_escape(&p.to_string_lossy())),
+                pf.modified.map_or_else(|| "-".to_string(), format_time),
+                pf.original_data.len(),
+                range,
+                html_escape(&layout.join(", ")),
+                html_escape(&pf.group),
+                pf.header_lines.iter().map(|l| html_escape(l)).collect::<Vec<_>>().join("<br>"),
+                diagnostics
+            ));
+        }
+        out.push_str("</table>\n");
+    }
+
+    // Complete report as a single HTML document (inline CSS and SVG, no external resources)
+    pub(crate) fn build_report(&self) -> String {
+        let base = self.display_base;
+        let common = &self.intersect_addresses;
+        let union: BTreeSet<u64> = self.files.iter().flat_map(|pf| pf.address_to_data.keys().copied()).collect();
+        let differing: Vec<u64> = common.iter().copied().filter(|&addr| is_data_different(&self.files, addr, base)).collect();
+        let regions = diff_regions(&differing);
+        let stats: Vec<_> = differing.iter().map(|&addr| address_stats(&self.files, addr, base)).collect();
+        let mean_entropy = if stats.is_empty() { 0.0 } else { stats.iter().map(|s| s.entropy).sum::<f64>() / stats.len() as f64 };
+
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>SuffixCode Comparison Report</title>\n<style>\n{}</style>\n</head>\n<body>\n<h1>SuffixCode Comparison Report</h1>\n<p>Generated {} · values in {:?}</p>\n",
+            STYLE,
+            format_time(SystemTime::now()),
+            base
+        );
+
+        self.report_files(&mut out);
+
+        out.push_str("<h2>Summary</h2>\n<table>\n");
+        let total_rows: usize = self.files.iter().map(|pf| pf.address_to_data.len()).sum();
+        let diagnostics: usize = self.files.iter().map(|pf| pf.diagnostics.len()).sum();
+        for (label, value) in [
+            ("Files", self.files.len().to_string()),
+            ("Total rows", total_rows.to_string()),
+            ("Addresses in any file", union.len().to_string()),
+            ("Addresses in every file", common.len().to_string()),
+            ("Differing addresses", format!("{} ({:.1}%)", differing.len(), percent(differing.len(), common.len()))),
+            ("Diff regions", regions.len().to_string()),
+            ("Mean entropy of differing addresses", format!("{:.3} bits", mean_entropy)),
+            ("Parser diagnostics", diagnostics.to_string()),
+        ] {
+            out.push_str(&format!("<tr><th>{}</th><td class=\"num\">{}</td></tr>\n", label, value));
+        }
+        out.push_str("</table>\n");
+
+        out.push_str("<h2>Diff Regions</h2>\n");
+        if regions.is_empty() {
+            out.push_str("<p>All files agree on every common address.</p>\n");
+        } else {
+            out.push_str("<table>\n<tr><th>Start</th><th>End</th><th>Differing addresses</th></tr>\n");
+            for r in &regions {
+                out.push_str(&format!("<tr><td class=\"mono\">{}</td><td class=\"mono\">{}</td><td class=\"num\">{}</td></tr>\n", format_addr(r.start), format_addr(r.end), r.count));
+            }
+            out.push_str("</table>\n");
+        }
+
+        out.push_str("<h2>Differing Addresses</h2>\n");
+        if !stats.is_empty() {
+            out.push_str("<table>\n<tr><th>Address</th><th>Distinct</th><th>Majority</th><th>Majority %</th><th>Entropy</th></tr>\n");
+            for s in &stats {
+                out.push_str(&format!(
+                    "<tr><td class=\"mono\">{}</td><td class=\"num\">{}</td><td class=\"mono\">{}</td><td class=\"num\">{:.1}%</td><td class=\"num\">{:.3}</td></tr>\n",
+                    format_addr(s.addr),
+                    s.distinct,
+                    html_escape(&s.majority),
+                    s.majority_pct,
+                    s.entropy
+                ));
+            }
+            out.push_str("</table>\n");
+            if differing.len() > MAX_CHARTS {
+                out.push_str(&format!("<p>Distribution charts for the first {} of {} differing addresses.</p>\n", MAX_CHARTS, differing.len()));
+            }
+            for &addr in differing.iter().take(MAX_CHARTS) {
+                out.push_str(&format!("<div class=\"chart\">\n{}</div>\n", self.stats_figure(addr, true).to_svg()));
+            }
+        } else {
+            out.push_str("<p>None.</p>\n");
+        }
+
+        out.push_str("<h2>Outlier Ranking</h2>\n");
+        let outliers = OutlierReport::compute(&self.files, common, base, self.data_revision);
+        let mut rows: Vec<_> = outliers.rows.iter().collect();
+        rows.sort_by(|a, b| b.addresses.len().cmp(&a.addresses.len()).then_with(|| a.file_idx.cmp(&b.file_idx)));
+        out.push_str("<table>\n<tr><th>Rank</th><th>File</th><th>Disagreements</th><th>%</th><th>Addresses</th></tr>\n");
+        for (rank, row) in rows.iter().enumerate() {
+            let mut addrs: Vec<String> = row.addresses.iter().take(MAX_OUTLIER_ADDRS).map(|a| format_addr(*a)).collect();
+            if row.addresses.len() > MAX_OUTLIER_ADDRS {
+                addrs.push(format!("… {} more", row.addresses.len() - MAX_OUTLIER_ADDRS));
+            }
+            out.push_str(&format!(
+                "<tr><td class=\"num\">{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{:.1}%</td><td class=\"mono\">{}</td></tr>\n",
+                rank + 1,
+                html_escape(&self.files[row.file_idx].file_name),
+                row.addresses.len(),
+                row.percent(),
+                addrs.join(" ")
+            ));
+        }
+        out.push_str("</table>\n");
+        if outliers.ties > 0 {
+            out.push_str(&format!("<p>{} tied address(es) without a single majority value were not ranked.</p>\n", outliers.ties));
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    pub(crate) fn generate_report(&self) {
+        if self.files.is_empty() {
+            warn!("No files loaded for the report");
+            return;
+        }
+        let html = self.build_report();
+        if let Some(path) = rfd::FileDialog::new().set_file_name("report.html").add_filter("HTML", &["html"]).save_file() {
+            if let Err(e) = fs::write(&path, html) {
+                error!("Report failed: {:?}", e);
+            } else {
+                info!("Report written: {}", path.to_string_lossy());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn merges_consecutive_addresses_into_regions() {
+        let regions = diff_regions(&[0x10, 0x11, 0x12, 0x20, 0x22, 0x23, u64::MAX]);
+        let spans: Vec<(u64, u64, usize)> = regions.iter().map(|r| (r.start, r.end, r.count)).collect();
+        assert_eq!(spans, [(0x10, 0x12, 3), (0x20, 0x20, 1), (0x22, 0x23, 2), (u64::MAX, u64::MAX, 1)]);
+        assert!(diff_regions(&[]).is_empty());
+    }
+
+    #[test]
+    fn formats_utc_dates() {
+        let at = |secs| format_time(UNIX_EPOCH + Duration::from_secs(secs));
+        assert_eq!(at(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(at(951_782_400), "2000-02-29 00:00:00 UTC");
+        assert_eq!(at(1_709_251_199), "2024-02-29 23:59:59 UTC");
+        assert_eq!(at(4_107_587_696), "2100-03-01 12:34:56 UTC");
+        assert_eq!(format_time(UNIX_EPOCH - Duration::from_secs(1)), "-");
+    }
+}
@@ -0,0 +1,473 @@
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use log::{error, info, warn};
+use std::collections::BTreeSet;
+use std::fs;
+
+use crate::{AppState, ParsedFile, csv_field, format_addr, parse_data_value, parse_user_number};
+
+// Failing addresses listed in a cell's hover text
+const MAX_DETAIL_ADDRS: usize = 32;
+
+// Expectation spec, one rule per line, '#' starts a comment:
+//   0x10 == 0x7E
+//   0x20 & 0x0F in {1, 2}
+//   0x30..0x3F != 0xFF        (address range is inclusive)
+//   0x40 not in {0x00, 0x10..0x1F}
+// Operators: == != < <= > >= in, not in
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum RuleOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    In,
+    NotIn,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Rule {
+    // 1-based line in the spec
+    pub(crate) line: usize,
+    pub(crate) text: String,
+    pub(crate) start: u64,
+    pub(crate) end: u64,
+    pub(crate) mask: Option<u64>,
+    pub(crate) op: RuleOp,
+    // Comparison operand(s); a single (v, v) for scalar operators, inclusive ranges for sets
+    pub(crate) operands: Vec<(u64, u64)>,
+}
+
+impl Rule {
+    fn check(&self, value: u64) -> bool {
+        let v = self.mask.map_or(value, |m| value & m);
+        let (lo, _) = self.operands[0];
+        match self.op {
+            RuleOp::Eq => v == lo,
+            RuleOp::Ne => v != lo,
+            RuleOp::Lt => v < lo,
+            RuleOp::Le => v <= lo,
+            RuleOp::Gt => v > lo,
+            RuleOp::Ge => v >= lo,
+            RuleOp::In => self.operands.iter().any(|&(a, b)| (a..=b).contains(&v)),
+            RuleOp::NotIn => !self.operands.iter().any(|&(a, b)| (a..=b).contains(&v)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Num(u64),
+    DotDot,
+    Amp,
+    Op(RuleOp),
+    In,
+    Not,
+    LBrace,
+    RBrace,
+    Comma,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            ' ' | '\t' => i += 1,
+            '&' => { tokens.push(Token::Amp); i += 1; }
+            '{' => { tokens.push(Token::LBrace); i += 1; }
+            '}' => { tokens.push(Token::RBrace); i += 1; }
+            ',' => { tokens.push(Token::Comma); i += 1; }
+            '.' if next == Some('.') => { tokens.push(Token::DotDot); i += 2; }
+            '=' if next == Some('=') => { tokens.push(Token::Op(RuleOp::Eq)); i += 2; }
+            '!' if next == Some('=') => { tokens.push(Token::Op(RuleOp::Ne)); i += 2; }
+            '<' if next == Some('=') => { tokens.push(Token::Op(RuleOp::Le)); i += 2; }
+            '>' if next == Some('=') => { tokens.push(Token::Op(RuleOp::Ge)); i += 2; }
+            '<' => { tokens.push(Token::Op(RuleOp::Lt)); i += 1; }
+            '>' => { tokens.push(Token::Op(RuleOp::Gt)); i += 1; }
+            c if c.is_ascii_alphanumeric() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                match word.to_ascii_lowercase().as_str() {
+                    "in" => tokens.push(Token::In),
+                    "not" => tokens.push(Token::Not),
+                    _ => tokens.push(Token::Num(parse_user_number(&word).ok_or_else(|| format!("invalid number '{}'", word))?)),
+                }
+            }
+            other => return Err(format!("unexpected character '{}'", other)),
+        }
+    }
+    Ok(tokens)
+}
+
+fn parse_rule(line: usize, text: &str) -> Result<Rule, String> {
+    let tokens = tokenize(text)?;
+    let mut it = tokens.into_iter().peekable();
+    let num = |it: &mut std::iter::Peekable<std::vec::IntoIter<Token>>, what: &str| match it.next() {
+        Some(Token::Num(n)) => Ok(n),
+        other => Err(format!("expected {}, found {:?}", what, other)),
+    };
+    let start = num(&mut it, "address")?;
+    let end = if it.peek() == Some(&Token::DotDot) {
+        it.next();
+        num(&mut it, "range end address")?
+    } else {
+        start
+    };
+    if end < start {
+        return Err("address range end is before start".to_string());
+    }
+    let mask = if it.peek() == Some(&Token::Amp) {
+        it.next();
+        Some(num(&mut it, "mask")?)
+    } else {
+        None
+    };
+    let op = match it.next() {
+        Some(Token::Op(op)) => op,
+        Some(Token::In) => RuleOp::In,
+        Some(Token::Not) if it.next() == Some(Token::In) => RuleOp::NotIn,
+        other => return Err(format!("expected operator, found {:?}", other)),
+    };
+    let operands = if matches!(op, RuleOp::In | RuleOp::NotIn) {
+        if it.next() != Some(Token::LBrace) {
+            return Err("expected '{' after in".to_string());
+        }
+        let mut set = Vec::new();
+        loop {
+            let lo = num(&mut it, "set value")?;
+            let hi = if it.peek() == Some(&Token::DotDot) {
+                it.next();
+                num(&mut it, "set range end")?
+            } else {
+                lo
+            };
+            set.push((lo.min(hi), lo.max(hi)));
+            match it.next() {
+                Some(Token::Comma) => continue,
+                Some(Token::RBrace) => break,
+                other => return Err(format!("expected ',' or '}}', found {:?}", other)),
+            }
+        }
+        set
+    } else {
+        let v = num(&mut it, "value")?;
+        vec![(v, v)]
+    };
+    if let Some(extra) = it.next() {
+        return Err(format!("unexpected {:?} at end of rule", extra));
+    }
+    Ok(Rule { line, text: text.to_string(), start, end, mask, op, operands })
+}
+
+// Parse a whole spec; returns the valid rules and "line N: message" errors
+pub(crate) fn parse_spec(spec: &str) -> (Vec<Rule>, Vec<String>) {
+    let mut rules = Vec::new();
+    let mut errors = Vec::new();
+    for (idx, raw) in spec.lines().enumerate() {
+        let text = raw.split('#').next().unwrap_or("").trim();
+        if text.is_empty() {
+            continue;
+        }
+        match parse_rule(idx + 1, text) {
+            Ok(rule) => rules.push(rule),
+            Err(e) => errors.push(format!("line {}: {}", idx + 1, e)),
+        }
+    }
+    (rules, errors)
+}
+
+// Result of one (rule, file) pair: present addresses whose value fails, plus how many
+// addresses of the range the file does not have at all
+#[derive(Clone, Debug, Default)]
+pub(crate) struct RuleOutcome {
+    pub(crate) failing: Vec<u64>,
+    pub(crate) missing: u64,
+}
+
+impl RuleOutcome {
+    pub(crate) fn passed(&self) -> bool {
+        self.failing.is_empty() && self.missing == 0
+    }
+
+    fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.failing.is_empty() {
+            parts.push(format!("{} failing", self.failing.len()));
+        }
+        if self.missing > 0 {
+            parts.push(format!("{} missing", self.missing));
+        }
+        parts.join(", ")
+    }
+}
+
+// Only the file's own addresses inside the range are visited, so wide ranges stay cheap
+fn evaluate(rule: &Rule, pf: &ParsedFile) -> RuleOutcome {
+    let mut failing = Vec::new();
+    let mut present = 0u64;
+    for (&addr, raw) in pf.address_to_data.range(rule.start..=rule.end) {
+        present += 1;
+        if !parse_data_value(raw).is_some_and(|v| rule.check(v)) {
+            failing.push(addr);
+        }
+    }
+    let missing = (rule.end - rule.start).saturating_add(1).saturating_sub(present);
+    RuleOutcome { failing, missing }
+}
+
+pub(crate) struct RuleReport {
+    revision: u64,
+    rules_revision: u64,
+    // failures[rule][file]
+    pub(crate) failures: Vec<Vec<RuleOutcome>>,
+    // (file index, address) pairs failing any rule, for table highlighting
+    pub(crate) failing_cells: BTreeSet<(usize, u64)>,
+}
+
+impl RuleReport {
+    fn compute(rules: &[Rule], files: &[ParsedFile], revision: u64, rules_revision: u64) -> Self {
+        let failures: Vec<Vec<RuleOutcome>> = rules.iter().map(|rule| files.iter().map(|pf| evaluate(rule, pf)).collect()).collect();
+        let failing_cells = failures
+            .iter()
+            .flat_map(|per_file| per_file.iter().enumerate().flat_map(|(f, outcome)| outcome.failing.iter().map(move |&a| (f, a))))
+            .collect();
+        Self { revision, rules_revision, failures, failing_cells }
+    }
+
+    pub(crate) fn failed(&self, file_idx: usize, addr: u64) -> bool {
+        self.failing_cells.contains(&(file_idx, addr))
+    }
+
+    fn to_csv(&self, rules: &[Rule], files: &[ParsedFile]) -> String {
+        let mut csv = String::from("line,rule,file,result,failing addresses,missing addresses\n");
+        for (rule, per_file) in rules.iter().zip(&self.failures) {
+            for (pf, outcome) in files.iter().zip(per_file) {
+                let addrs = outcome.failing.iter().map(|a| format_addr(*a)).collect::<Vec<_>>().join(" ");
+                csv.push_str(&format!(
+                    "{},{},{},{},{},{}\n",
+                    rule.line,
+                    csv_field(&rule.text),
+                    csv_field(&pf.file_name),
+                    if outcome.passed() { "PASS" } else { "FAIL" },
+                    addrs,
+                    outcome.missing
+                ));
+            }
+        }
+        csv
+    }
+}
+
+impl AppState {
+    fn apply_rules_text(&mut self) {
+        let (rules, errors) = parse_spec(&self.rules_text);
+        for e in &errors {
+            warn!("Rule spec {}", e);
+        }
+        info!("Loaded {} rule(s)", rules.len());
+        self.rules = rules;
+        self.rule_errors = errors;
+        self.rules_revision += 1;
+    }
+
+    // Re-evaluate the loaded rules when files or rules changed
+    pub(crate) fn refresh_rule_report(&mut self) {
+        if self.rules.is_empty() {
+            self.rule_report = None;
+            return;
+        }
+        let stale = self
+            .rule_report
+            .as_ref()
+            .is_none_or(|r| r.revision != self.data_revision || r.rules_revision != self.rules_revision);
+        if stale {
+            self.rule_report = Some(RuleReport::compute(&self.rules, &self.files, self.data_revision, self.rules_revision));
+        }
+    }
+
+    pub(crate) fn show_rules_window(&mut self, ctx: &egui::Context) {
+        let main_rect = ctx.input(|i| i.screen_rect());
+        let mut open = self.show_rules;
+        egui::Window::new("Rules")
+            .constrain_to(main_rect)
+            .max_size(main_rect.size())
+            .open(&mut open)
+            .show(ctx, |ui| self.rules_ui(ui));
+        self.show_rules = open;
+    }
+
+    fn rules_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("Load Spec").clicked()
+                && let Some(path) = rfd::FileDialog::new().add_filter("Rules", &["txt", "rules"]).pick_file()
+            {
+                match fs::read_to_string(&path) {
+                    Ok(text) => {
+                        self.rules_text = text;
+                        self.apply_rules_text();
+                    }
+                    Err(e) => error!("Load spec failed: {:?}", e),
+                }
+            }
+            if ui.button("Apply").clicked() {
+                self.apply_rules_text();
+            }
+            let can_export = self.rule_report.is_some() && !self.files.is_empty();
+            if ui.add_enabled(can_export, egui::Button::new("Export Report")).clicked()
+                && let Some(report) = &self.rule_report
+                && let Some(path) = rfd::FileDialog::new().set_file_name("rules_report.csv").save_file()
+            {
+                if let Err(e) = fs::write(&path, report.to_csv(&self.rules, &self.files)) {
+                    error!("Export failed: {:?}", e);
+                } else {
+                    info!("Exported: {}", path.to_string_lossy());
+                }
+            }
+        });
+        ui.add(
+            egui::TextEdit::multiline(&mut self.rules_text)
+                .code_editor()
+                .desired_rows(6)
+                .desired_width(f32::INFINITY)
+                .hint_text("0x10 == 0x7E\n0x20 & 0x0F in {1,2}\n0x30..0x3F != 0xFF"),
+        );
+        for e in &self.rule_errors {
+            ui.colored_label(egui::Color32::RED, e);
+        }
+
+        self.refresh_rule_report();
+        let Some(report) = &self.rule_report else {
+            ui.label("No rules loaded.");
+            return;
+        };
+        if self.files.is_empty() {
+            ui.label("No files loaded.");
+            return;
+        }
+        let failed_files = (0..self.files.len()).filter(|&f| report.failures.iter().any(|r| !r[f].passed())).count();
+        ui.label(format!("Rules: {}  |  Files failing: {} of {}", self.rules.len(), failed_files, self.files.len()));
+        ui.separator();
+
+        egui::ScrollArea::horizontal().show(ui, |ui| {
+            let mut table = TableBuilder::new(ui).striped(true).max_scroll_height(360.0).column(Column::initial(220.0).resizable(true));
+            for _ in &self.files {
+                table = table.column(Column::initial(90.0).resizable(true));
+            }
+            table
+                .header(24.0, |mut header| {
+                    header.col(|ui| { ui.label("Rule"); });
+                    for pf in &self.files {
+                        header.col(|ui| { ui.add(egui::Label::new(&pf.file_name).wrap(true)); });
+                    }
+                })
+                .body(|body| {
+                    body.rows(20.0, self.rules.len(), |mut row| {
+                        let idx = row.index();
+                        let rule = &self.rules[idx];
+                        row.col(|ui| { ui.monospace(&rule.text); });
+                        for (pf, outcome) in self.files.iter().zip(&report.failures[idx]) {
+                            row.col(|ui| {
+                                if outcome.passed() {
+                                    ui.colored_label(egui::Color32::from_rgb(0, 150, 0), "PASS");
+                                } else {
+                                    let mut detail: Vec<String> = outcome
+                                        .failing
+                                        .iter()
+                                        .take(MAX_DETAIL_ADDRS)
+                                        .map(|a| format!("{} = {}", format_addr(*a), pf.address_to_data.get(a).map(String::as_str).unwrap_or("")))
+                                        .collect();
+                                    if outcome.failing.len() > MAX_DETAIL_ADDRS {
+                                        detail.push(format!("… {} more", outcome.failing.len() - MAX_DETAIL_ADDRS));
+                                    }
+                                    if outcome.missing > 0 {
+                                        detail.push(format!("{} address(es) missing", outcome.missing));
+                                    }
+                                    ui.colored_label(egui::Color32::RED, format!("FAIL ({})", outcome.summary())).on_hover_text(detail.join("\n"));
+                                }
+                            });
+                        }
+                    });
+                });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsed_file;
+
+    #[test]
+    fn parses_valid_rules() {
+        let spec = "# header comment\n0x10 == 0x7E\n\n0x20 & 0x0F in {1, 2}  # trailing\n0x30..0x3F != 0xFF\n0x40 not in {0x00, 0x1F..0x10}\n0x50 <= 3\n";
+        let (rules, errors) = parse_spec(spec);
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(rules.len(), 5);
+
+        assert_eq!((rules[0].line, rules[0].start, rules[0].end, rules[0].op), (2, 0x10, 0x10, RuleOp::Eq));
+        assert_eq!(rules[0].operands, vec![(0x7E, 0x7E)]);
+
+        assert_eq!((rules[1].line, rules[1].mask, rules[1].op), (4, Some(0x0F), RuleOp::In));
+        assert_eq!(rules[1].operands, vec![(1, 1), (2, 2)]);
+        assert_eq!(rules[1].text, "0x20 & 0x0F in {1, 2}");
+
+        assert_eq!((rules[2].start, rules[2].end, rules[2].op), (0x30, 0x3F, RuleOp::Ne));
+
+        // Set ranges are normalised to (low, high)
+        assert_eq!(rules[3].op, RuleOp::NotIn);
+        assert_eq!(rules[3].operands, vec![(0x00, 0x00), (0x10, 0x1F)]);
+
+        assert_eq!(rules[4].op, RuleOp::Le);
+    }
+
+    #[test]
+    fn reports_malformed_lines_and_keeps_the_rest() {
+        let spec = "0x10 == 1\n0x10 5\n0x10 in 1, 2}\n0x10 == 1 2\n0x10 ? 1\n0x10 in {1, 2\n0xZZ == 1\n0x11 > 2";
+        let (rules, errors) = parse_spec(spec);
+        assert_eq!(rules.iter().map(|r| r.line).collect::<Vec<_>>(), vec![1, 8]);
+        assert_eq!(errors.len(), 6);
+        assert!(errors[0].starts_with("line 2: expected operator"), "{}", errors[0]);
+        assert_eq!(errors[1], "line 3: expected '{' after in");
+        assert!(errors[2].starts_with("line 4: unexpected Num(2) at end of rule"), "{}", errors[2]);
+        assert_eq!(errors[3], "line 5: unexpected character '?'");
+        assert!(errors[4].starts_with("line 6: expected ',' or '}'"), "{}", errors[4]);
+        assert_eq!(errors[5], "line 7: invalid number '0xZZ'");
+    }
+
+    #[test]
+    fn rejects_inverted_address_range() {
+        let (rules, errors) = parse_spec("0x20..0x10 == 0");
+        assert!(rules.is_empty());
+        assert_eq!(errors, vec!["line 1: address range end is before start".to_string()]);
+    }
+
+    #[test]
+    fn evaluate_separates_failing_and_missing() {
+        let (rules, _) = parse_spec("0x10..0x14 == 0x7E");
+        let pf = parsed_file(&[(0x10, "7E"), (0x11, "00"), (0x13, "7E"), (0x20, "00")]);
+        let outcome = evaluate(&rules[0], &pf);
+        assert_eq!(outcome.failing, vec![0x11]);
+        assert_eq!(outcome.missing, 2);
+        assert!(!outcome.passed());
+    }
+
+    #[test]
+    fn evaluate_handles_the_full_address_space() {
+        let rule = parse_rule(1, "0..0xFFFFFFFFFFFFFFFF != 0xFF").unwrap();
+        let pf = parsed_file(&[(0, "01"), (u64::MAX, "FF")]);
+        let outcome = evaluate(&rule, &pf);
+        assert_eq!(outcome.failing, vec![u64::MAX]);
+        // 2^64 addresses do not fit in u64; the count saturates instead of overflowing
+        assert_eq!(outcome.missing, u64::MAX - 2);
+    }
+}
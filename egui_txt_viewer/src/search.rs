@@ -0,0 +1,224 @@
+use eframe::egui;
+use std::collections::BTreeSet;
+
+use crate::{AppState, DisplayBase, format_addr, format_data_with_base, parse_data_value, parse_user_number};
+
+// Parse search input: 0x / 0b / # (decimal) prefixes and an h suffix are explicit,
+// bare digits are read in the current display base. "0d" is not a decimal marker
+// because it is a valid bare hex value.
+pub(crate) fn parse_in_base(text: &str, base: DisplayBase) -> Option<u64> {
+    let t = text.trim().replace('_', "");
+    let lower = t.to_ascii_lowercase();
+    if let Some(dec) = lower.strip_prefix('#') {
+        return dec.parse::<u64>().ok();
+    }
+    if lower.starts_with("0x") || lower.starts_with("0b") || lower.ends_with('h') {
+        return parse_user_number(&lower);
+    }
+    match base {
+        DisplayBase::Hex => u64::from_str_radix(&lower, 16).ok(),
+        DisplayBase::Bin => u64::from_str_radix(&lower, 2).ok(),
+        DisplayBase::Dec => lower.parse::<u64>().ok(),
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct SearchState {
+    addr_text: String,
+    value_text: String,
+    // None searches every file, Some(i) only file column i
+    scope: Option<usize>,
+    // Matching cells (address, file index) in table order
+    hits: Vec<(u64, usize)>,
+    hit_set: BTreeSet<(u64, usize)>,
+    current: Option<usize>,
+    // (data revision, filter revision, display base) the hits were collected under
+    hits_for: Option<(u64, u64, DisplayBase)>,
+    message: Option<String>,
+}
+
+impl SearchState {
+    // Some(true) for the current hit, Some(false) for other hits
+    pub(crate) fn hit_at(&self, addr: u64, file_idx: usize) -> Option<bool> {
+        if !self.hit_set.contains(&(addr, file_idx)) {
+            return None;
+        }
+        Some(self.current.and_then(|c| self.hits.get(c)) == Some(&(addr, file_idx)))
+    }
+}
+
+impl AppState {
+    pub(crate) fn show_search_window(&mut self, ctx: &egui::Context) {
+        let main_rect = ctx.input(|i| i.screen_rect());
+        let mut open = self.show_search;
+        egui::Window::new("Search")
+            .constrain_to(main_rect)
+            .max_size(main_rect.size())
+            .open(&mut open)
+            .show(ctx, |ui| self.search_ui(ui));
+        self.show_search = open;
+    }
+
+    // Re-run the last search when rows or formatting changed underneath it; called every frame
+    // so highlights and F3 never use stale hits while the window is closed
+    pub(crate) fn refresh_search_hits(&mut self) {
+        if self.search.scope.is_some_and(|i| i >= self.files.len()) {
+            self.search.scope = None;
+        }
+        if self.search.hits_for.is_some() && self.search.hits_for != Some((self.data_revision, self.filter_revision, self.display_base)) {
+            self.find_values();
+        }
+    }
+
+    fn search_ui(&mut self, ui: &mut egui::Ui) {
+        ui.label("Bare digits use the current base; 0x.., 0b.., #.. (decimal) or ..h override it.");
+        ui.horizontal(|ui| {
+            ui.label("Go to address:");
+            let resp = ui.add(egui::TextEdit::singleline(&mut self.search.addr_text).hint_text("0x100").desired_width(120.0));
+            let enter = resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if ui.button("Go").clicked() || enter {
+                self.go_to_address();
+            }
+        });
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("Find value:");
+            let resp = ui.add(egui::TextEdit::singleline(&mut self.search.value_text).hint_text("7E").desired_width(120.0));
+            let enter = resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            let scope_text = match self.search.scope {
+                Some(i) => self.files[i].file_name.clone(),
+                None => "Any file".to_string(),
+            };
+            egui::ComboBox::from_id_source("search_scope").selected_text(scope_text).show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.search.scope, None, "Any file");
+                for (idx, pf) in self.files.iter().enumerate() {
+                    ui.selectable_value(&mut self.search.scope, Some(idx), &pf.file_name);
+                }
+            });
+            if ui.button("Find").clicked() || enter {
+                self.find_values();
+                self.step_hit(true);
+            }
+        });
+        ui.horizontal(|ui| {
+            let has_hits = !self.search.hits.is_empty();
+            if ui.add_enabled(has_hits, egui::Button::new("◀ Previous")).clicked() {
+                self.step_hit(false);
+            }
+            if ui.add_enabled(has_hits, egui::Button::new("Next ▶")).clicked() {
+                self.step_hit(true);
+            }
+            if ui.button("Clear").clicked() {
+                self.search.hits.clear();
+                self.search.hit_set.clear();
+                self.search.current = None;
+                self.search.hits_for = None;
+                self.search.message = None;
+            }
+            if let Some(c) = self.search.current {
+                ui.label(format!("Hit {} of {}", c + 1, self.search.hits.len()));
+            }
+        });
+        if let Some(msg) = &self.search.message {
+            ui.label(msg);
+        }
+        ui.label("F3 / Shift+F3: next / previous hit");
+    }
+
+    fn go_to_address(&mut self) {
+        let Some(addr) = parse_in_base(&self.search.addr_text, self.display_base) else {
+            self.search.message = Some("Enter a valid address.".to_string());
+            return;
+        };
+        self.search.message = match self.intersect_addresses.binary_search(&addr) {
+            Ok(i) if self.visible_rows.contains(&i) => {
+                self.select_addr(addr);
+                None
+            }
+            Ok(_) => Some(format!("{} is hidden by the row filter.", format_addr(addr))),
+            Err(_) => Some(format!("{} is not present in every file.", format_addr(addr))),
+        };
+    }
+
+    // Collect hits over the visible rows; numeric queries compare values, others the displayed text
+    fn find_values(&mut self) {
+        let query = self.search.value_text.trim().to_string();
+        let number = parse_in_base(&query, self.display_base);
+        let text = query.to_lowercase();
+        let mut hits = Vec::new();
+        if !query.is_empty() {
+            for &row in &self.visible_rows {
+                let addr = self.intersect_addresses[row];
+                for (file_idx, pf) in self.files.iter().enumerate() {
+                    if self.search.scope.is_some_and(|s| s != file_idx) {
+                        continue;
+                    }
+                    let Some(raw) = pf.address_to_data.get(&addr) else { continue };
+                    let found = match number {
+                        Some(n) => parse_data_value(raw) == Some(n),
+                        None => format_data_with_base(raw, self.display_base).to_lowercase() == text,
+                    };
+                    if found {
+                        hits.push((addr, file_idx));
+                    }
+                }
+            }
+        }
+        // Keep the current hit when it still exists so re-runs do not jump around
+        let previous = self.search.current.and_then(|c| self.search.hits.get(c)).copied();
+        self.search.current = previous.and_then(|p| hits.iter().position(|&h| h == p));
+        self.search.message = if query.is_empty() {
+            None
+        } else if hits.is_empty() {
+            Some("No matches.".to_string())
+        } else {
+            Some(format!("{} matching cells", hits.len()))
+        };
+        self.search.hit_set = hits.iter().copied().collect();
+        self.search.hits = hits;
+        self.search.hits_for = Some((self.data_revision, self.filter_revision, self.display_base));
+    }
+
+    pub(crate) fn step_hit(&mut self, forward: bool) {
+        let n = self.search.hits.len();
+        if n == 0 {
+            return;
+        }
+        let next = match self.search.current {
+            Some(c) if forward => (c + 1) % n,
+            Some(c) => (c + n - 1) % n,
+            None if forward => 0,
+            None => n - 1,
+        };
+        self.search.current = Some(next);
+        let (addr, _) = self.search.hits[next];
+        self.select_addr(addr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bare_digits_follow_the_display_base() {
+        assert_eq!(parse_in_base("10", DisplayBase::Hex), Some(0x10));
+        assert_eq!(parse_in_base("10", DisplayBase::Dec), Some(10));
+        assert_eq!(parse_in_base("10", DisplayBase::Bin), Some(2));
+        assert_eq!(parse_in_base("0d", DisplayBase::Hex), Some(0x0D));
+        assert_eq!(parse_in_base("7E", DisplayBase::Dec), None);
+    }
+
+    #[test]
+    fn explicit_markers_override_the_display_base() {
+        for base in [DisplayBase::Hex, DisplayBase::Dec, DisplayBase::Bin] {
+            assert_eq!(parse_in_base("0x10", base), Some(0x10));
+            assert_eq!(parse_in_base("10h", base), Some(0x10));
+            assert_eq!(parse_in_base("0b10", base), Some(2));
+            assert_eq!(parse_in_base("#10", base), Some(10));
+            assert_eq!(parse_in_base(" #1_000 ", base), Some(1000));
+        }
+        assert_eq!(parse_in_base("#7E", DisplayBase::Hex), None);
+    }
+}
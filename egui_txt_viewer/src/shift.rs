@@ -0,0 +1,238 @@
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use std::collections::HashMap;
+
+use crate::{AppState, DisplayBase, ParsedFile, format_addr, format_data_with_base};
+
+// Candidates shorter than this overlap are ignored (too few bytes to be meaningful)
+const MIN_OVERLAP: usize = 8;
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ShiftCandidate {
+    // B's data at addr + shift lines up with A's data at addr
+    pub(crate) shift: i64,
+    pub(crate) matches: usize,
+    pub(crate) overlap: usize,
+}
+
+impl ShiftCandidate {
+    pub(crate) fn ratio(&self) -> f64 {
+        if self.overlap == 0 { 0.0 } else { self.matches as f64 / self.overlap as f64 }
+    }
+}
+
+pub(crate) struct ShiftState {
+    pub(crate) a: usize,
+    pub(crate) b: usize,
+    pub(crate) max_shift: i64,
+    // Best candidates first; (data revision, a, b, max_shift) they were computed for
+    candidates: Vec<ShiftCandidate>,
+    computed_for: Option<(u64, usize, usize, i64)>,
+    chosen: Option<i64>,
+    diff_only: bool,
+}
+
+impl Default for ShiftState {
+    fn default() -> Self {
+        Self { a: 0, b: 1, max_shift: 64, candidates: Vec::new(), computed_for: None, chosen: None, diff_only: true }
+    }
+}
+
+fn normalized(pf: &ParsedFile) -> HashMap<u64, String> {
+    pf.address_to_data.iter().map(|(addr, raw)| (*addr, format_data_with_base(raw, DisplayBase::Hex))).collect()
+}
+
+fn shifted(addr: u64, shift: i64) -> Option<u64> {
+    addr.checked_add_signed(shift)
+}
+
+// Score every shift in -max..=max by the fraction of overlapping addresses whose values agree
+pub(crate) fn detect_shifts(a: &ParsedFile, b: &ParsedFile, max_shift: i64) -> Vec<ShiftCandidate> {
+    let va = normalized(a);
+    let vb = normalized(b);
+    let mut candidates: Vec<ShiftCandidate> = (-max_shift..=max_shift)
+        .map(|shift| {
+            let (mut matches, mut overlap) = (0, 0);
+            for (addr, value) in &va {
+                if let Some(other) = shifted(*addr, shift).and_then(|s| vb.get(&s)) {
+                    overlap += 1;
+                    if other == value {
+                        matches += 1;
+                    }
+                }
+            }
+            ShiftCandidate { shift, matches, overlap }
+        })
+        .filter(|c| c.overlap >= MIN_OVERLAP)
+        .collect();
+    // Highest agreement first; ties prefer the smaller shift
+    candidates.sort_by(|x, y| y.ratio().total_cmp(&x.ratio()).then_with(|| x.shift.abs().cmp(&y.shift.abs())));
+    candidates
+}
+
+// Compared rows for a shift: (address in A, A value, B value at address + shift)
+fn aligned_rows(a: &ParsedFile, b: &ParsedFile, shift: i64, display_base: DisplayBase) -> Vec<(u64, String, String)> {
+    a.address_to_data
+        .iter()
+        .filter_map(|(addr, raw)| {
+            let other = b.address_to_data.get(&shifted(*addr, shift)?)?;
+            Some((*addr, format_data_with_base(raw, display_base), format_data_with_base(other, display_base)))
+        })
+        .collect()
+}
+
+impl AppState {
+    pub(crate) fn show_shift_window(&mut self, ctx: &egui::Context) {
+        let main_rect = ctx.input(|i| i.screen_rect());
+        let mut open = self.show_shift;
+        egui::Window::new("Shift Detection")
+            .constrain_to(main_rect)
+            .max_size(main_rect.size())
+            .open(&mut open)
+            .show(ctx, |ui| self.shift_ui(ui));
+        self.show_shift = open;
+    }
+
+    fn shift_ui(&mut self, ui: &mut egui::Ui) {
+        if self.files.len() < 2 {
+            ui.label("Load at least two files.");
+            return;
+        }
+        let state = &mut self.shift;
+        state.a = state.a.min(self.files.len() - 1);
+        state.b = state.b.min(self.files.len() - 1);
+        ui.horizontal(|ui| {
+            for (label, idx, id) in [("Reference:", &mut state.a, "shift_a"), ("Shifted:", &mut state.b, "shift_b")] {
+                ui.label(label);
+                egui::ComboBox::from_id_source(id).selected_text(self.files[*idx].file_name.as_str()).show_ui(ui, |ui| {
+                    for (i, pf) in self.files.iter().enumerate() {
+                        ui.selectable_value(idx, i, &pf.file_name);
+                    }
+                });
+            }
+            ui.add(egui::DragValue::new(&mut state.max_shift).clamp_range(1..=4096).prefix("Max shift ±"));
+        });
+        if state.a == state.b {
+            ui.label("Choose two different files.");
+            return;
+        }
+        let key = (self.data_revision, state.a, state.b, state.max_shift);
+        if ui.button("Detect").clicked() {
+            state.candidates = detect_shifts(&self.files[state.a], &self.files[state.b], state.max_shift);
+            state.computed_for = Some(key);
+            state.chosen = state.candidates.first().map(|c| c.shift);
+        }
+        if state.computed_for != Some(key) {
+            ui.label("Press Detect to score address shifts by cross-correlation.");
+            return;
+        }
+        if state.candidates.is_empty() {
+            ui.label("The files do not overlap at any shift in range.");
+            return;
+        }
+
+        ui.separator();
+        let unshifted = state.candidates.iter().find(|c| c.shift == 0).copied();
+        ui.label("Best shifts (click to preview):");
+        ui.horizontal_wrapped(|ui| {
+            for c in state.candidates.iter().take(8) {
+                let text = format!("{:+} ({:.1}%)", c.shift, c.ratio() * 100.0);
+                if ui.selectable_label(state.chosen == Some(c.shift), text).clicked() {
+                    state.chosen = Some(c.shift);
+                }
+            }
+        });
+        let Some(shift) = state.chosen else { return };
+        let Some(chosen) = state.candidates.iter().find(|c| c.shift == shift).copied() else { return };
+        if let Some(base) = unshifted {
+            ui.label(format!("Unshifted: {} of {} values agree ({:.1}%)", base.matches, base.overlap, base.ratio() * 100.0));
+        }
+        ui.label(format!(
+            "Shift {:+}: {} of {} values agree ({:.1}%)",
+            shift,
+            chosen.matches,
+            chosen.overlap,
+            chosen.ratio() * 100.0
+        ));
+        if shift != 0 && unshifted.is_none_or(|u| chosen.ratio() > u.ratio()) {
+            ui.colored_label(
+                egui::Color32::from_rgb(0, 120, 200),
+                format!("Suggestion: rebase {} by {:+} to line it up with {}.", self.files[state.b].file_name, -shift, self.files[state.a].file_name),
+            );
+        }
+
+        let mut apply = false;
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut state.diff_only, "Only differences");
+            if ui.add_enabled(shift != 0, egui::Button::new("Apply Rebase")).clicked() {
+                apply = true;
+            }
+        });
+        ui.separator();
+        ui.label("Rebased diff preview (From = original address in the shifted file):");
+        let (fa, fb) = (&self.files[state.a], &self.files[state.b]);
+        let rows: Vec<(u64, String, String)> = aligned_rows(fa, fb, shift, self.display_base)
+            .into_iter()
+            .filter(|(_, va, vb)| !state.diff_only || va != vb)
+            .collect();
+        TableBuilder::new(ui)
+            .striped(true)
+            .max_scroll_height(320.0)
+            .column(Column::initial(120.0).resizable(true))
+            .column(Column::initial(120.0).resizable(true))
+            .column(Column::initial(140.0).resizable(true))
+            .column(Column::remainder())
+            .header(24.0, |mut header| {
+                header.col(|ui| { ui.label("Address"); });
+                header.col(|ui| { ui.label("Reference"); });
+                header.col(|ui| { ui.label("Rebased"); });
+                header.col(|ui| { ui.label("From"); });
+            })
+            .body(|body| {
+                body.rows(20.0, rows.len(), |mut row| {
+                    let (addr, va, vb) = &rows[row.index()];
+                    row.col(|ui| { ui.monospace(format_addr(*addr)); });
+                    row.col(|ui| { ui.monospace(va); });
+                    row.col(|ui| {
+                        if va == vb {
+                            ui.monospace(vb);
+                        } else {
+                            ui.colored_label(egui::Color32::RED, egui::RichText::new(vb).monospace());
+                        }
+                    });
+                    row.col(|ui| { ui.monospace(shifted(*addr, shift).map(format_addr).unwrap_or_default()); });
+                });
+            });
+
+        if apply {
+            let b = state.b;
+            state.computed_for = None;
+            self.rebase_file(b, -shift);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsed_file;
+
+    #[test]
+    fn finds_the_shift_and_drops_short_overlaps() {
+        let values: Vec<String> = (0..16u64).map(|i| format!("{:02X}", (i * 37 + 5) & 0xFF)).collect();
+        let a = parsed_file(&values.iter().enumerate().map(|(i, v)| (i as u64, v.as_str())).collect::<Vec<_>>());
+        let b = parsed_file(&values.iter().enumerate().map(|(i, v)| (i as u64 + 3, v.as_str())).collect::<Vec<_>>());
+        let candidates = detect_shifts(&a, &b, 8);
+        assert_eq!((candidates[0].shift, candidates[0].matches, candidates[0].overlap), (3, 16, 16));
+        assert!(candidates[1..].iter().all(|c| c.ratio() < 1.0));
+        // Shifts below -5 overlap fewer than MIN_OVERLAP addresses
+        let mut shifts: Vec<i64> = candidates.iter().map(|c| c.shift).collect();
+        shifts.sort();
+        assert_eq!(shifts, (-5..=8).collect::<Vec<_>>());
+        assert!(candidates.iter().all(|c| c.overlap >= MIN_OVERLAP));
+
+        let rows = aligned_rows(&a, &b, 3, DisplayBase::Hex);
+        assert_eq!(rows.len(), 16);
+        assert!(rows.iter().all(|(_, x, y)| x == y));
+    }
+}
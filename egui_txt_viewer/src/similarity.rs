@@ -0,0 +1,292 @@
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+
+use crate::{AppState, ParsedFile, format_addr, format_data_with_base, parse_data_value};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub(crate) enum DistanceMetric {
+    #[default]
+    Bytes,
+    Bits,
+}
+
+// Cached pairwise distances between all loaded files over the compared addresses
+pub(crate) struct SimilarityMatrix {
+    revision: u64,
+    metric: DistanceMetric,
+    // distances[i][j] = differing bytes/bits between files[i] and files[j]
+    distances: Vec<Vec<u64>>,
+    // File indices in hierarchical clustering (average linkage) leaf order
+    order: Vec<usize>,
+    max_distance: u64,
+}
+
+// Distance between two data cells; unparsable cells count as a full byte when they differ
+fn cell_distance(a: &str, b: &str, metric: DistanceMetric) -> u64 {
+    match (parse_data_value(a), parse_data_value(b)) {
+        (Some(x), Some(y)) => match metric {
+            DistanceMetric::Bytes => u64::from(x != y),
+            DistanceMetric::Bits => u64::from((x ^ y).count_ones()),
+        },
+        _ if a.trim() == b.trim() => 0,
+        _ => match metric {
+            DistanceMetric::Bytes => 1,
+            DistanceMetric::Bits => 8,
+        },
+    }
+}
+
+fn pairwise_distances(files: &[ParsedFile], addrs: &[u64], metric: DistanceMetric) -> Vec<Vec<u64>> {
+    // Look every cell up once so the O(n²) loop only walks plain slices
+    let columns: Vec<Vec<&str>> = files
+        .iter()
+        .map(|pf| {
+            addrs
+                .iter()
+                .map(|addr| pf.address_to_data.get(addr).map(String::as_str).unwrap_or(""))
+                .collect()
+        })
+        .collect();
+    let n = files.len();
+    let mut distances = vec![vec![0u64; n]; n];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let d: u64 = columns[i]
+                .iter()
+                .zip(&columns[j])
+                .map(|(a, b)| cell_distance(a, b, metric))
+                .sum();
+            distances[i][j] = d;
+            distances[j][i] = d;
+        }
+    }
+    distances
+}
+
+// Agglomerative clustering with average linkage; returns the leaf order of the final tree
+fn cluster_order(distances: &[Vec<u64>]) -> Vec<usize> {
+    let n = distances.len();
+    let mut clusters: Vec<Vec<usize>> = (0..n).map(|i| vec![i]).collect();
+    let mut d: Vec<Vec<f64>> = distances
+        .iter()
+        .map(|row| row.iter().map(|&v| v as f64).collect())
+        .collect();
+    let mut alive = vec![true; n];
+    for _ in 1..n {
+        let mut best: Option<(usize, usize, f64)> = None;
+        for i in (0..n).filter(|&i| alive[i]) {
+            for j in ((i + 1)..n).filter(|&j| alive[j]) {
+                if best.is_none_or(|(_, _, b)| d[i][j] < b) {
+                    best = Some((i, j, d[i][j]));
+                }
+            }
+        }
+        let Some((i, j, _)) = best else { break };
+        let (ni, nj) = (clusters[i].len() as f64, clusters[j].len() as f64);
+        for k in (0..n).filter(|&k| alive[k] && k != i && k != j) {
+            let merged = (ni * d[k][i] + nj * d[k][j]) / (ni + nj);
+            d[i][k] = merged;
+            d[k][i] = merged;
+        }
+        let moved = std::mem::take(&mut clusters[j]);
+        clusters[i].extend(moved);
+        alive[j] = false;
+    }
+    clusters.into_iter().flatten().collect()
+}
+
+impl SimilarityMatrix {
+    fn compute(files: &[ParsedFile], addrs: &[u64], metric: DistanceMetric, revision: u64) -> Self {
+        let distances = pairwise_distances(files, addrs, metric);
+        let order = cluster_order(&distances);
+        let max_distance = distances.iter().flatten().copied().max().unwrap_or(0);
+        Self { revision, metric, distances, order, max_distance }
+    }
+}
+
+impl AppState {
+    pub(crate) fn show_similarity_window(&mut self, ctx: &egui::Context) {
+        let main_rect = ctx.input(|i| i.screen_rect());
+        let mut open = self.show_similarity;
+        egui::Window::new("Similarity")
+            .constrain_to(main_rect)
+            .max_size(main_rect.size())
+            .open(&mut open)
+            .show(ctx, |ui| self.similarity_ui(ui));
+        self.show_similarity = open;
+    }
+
+    fn similarity_ui(&mut self, ui: &mut egui::Ui) {
+        if self.files.len() < 2 {
+            ui.label("Load at least two files to compare.");
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.label("Distance:");
+            ui.radio_value(&mut self.similarity_metric, DistanceMetric::Bytes, "Differing bytes");
+            ui.radio_value(&mut self.similarity_metric, DistanceMetric::Bits, "Differing bits");
+        });
+        let stale = self
+            .similarity
+            .as_ref()
+            .is_none_or(|m| m.revision != self.data_revision || m.metric != self.similarity_metric);
+        if stale {
+            self.similarity = Some(SimilarityMatrix::compute(
+                &self.files,
+                &self.intersect_addresses,
+                self.similarity_metric,
+                self.data_revision,
+            ));
+        }
+        let Some(matrix) = &self.similarity else { return };
+        ui.label(format!(
+            "Compared addresses: {}  |  Max distance: {}",
+            self.intersect_addresses.len(),
+            matrix.max_distance
+        ));
+        ui.label("Rows/columns are ordered by hierarchical clustering. Click a cell to diff two files.");
+        ui.separator();
+
+        let n = matrix.order.len();
+        let label_size = 36.0;
+        let cell = (420.0 / n as f32).clamp(6.0, 28.0);
+        let side = label_size + cell * n as f32;
+        egui::ScrollArea::both().max_height(480.0).show(ui, |ui| {
+            let (rect, resp) = ui.allocate_exact_size(egui::vec2(side, side), egui::Sense::click());
+            let grid_min = rect.min + egui::vec2(label_size, label_size);
+            let max = matrix.max_distance.max(1) as f32;
+            let mut shapes = Vec::new();
+            for (r, &fi) in matrix.order.iter().enumerate() {
+                for (c, &fj) in matrix.order.iter().enumerate() {
+                    let t = matrix.distances[fi][fj] as f32 / max;
+                    let fade = (255.0 * (1.0 - t)) as u8;
+                    let cell_rect = egui::Rect::from_min_size(
+                        grid_min + egui::vec2(c as f32 * cell, r as f32 * cell),
+                        egui::vec2(cell, cell),
+                    );
+                    shapes.push(egui::Shape::rect_filled(cell_rect.shrink(0.5), 0.0, egui::Color32::from_rgb(255, fade, fade)));
+                }
+                // Index labels along both axes when there is room for them
+                if cell >= 12.0 {
+                    let text = format!("#{}", fi);
+                    let left = ui.painter().layout_no_wrap(text.clone(), egui::FontId::monospace(10.0), egui::Color32::BLACK);
+                    let top = ui.painter().layout_no_wrap(text, egui::FontId::monospace(10.0), egui::Color32::BLACK);
+                    let y = grid_min.y + r as f32 * cell + (cell - left.size().y) * 0.5;
+                    let x = grid_min.x + r as f32 * cell + (cell - top.size().x) * 0.5;
+                    shapes.push(egui::Shape::galley(egui::pos2(rect.left(), y), left, egui::Color32::BLACK));
+                    shapes.push(egui::Shape::galley(egui::pos2(x, rect.top() + label_size - 14.0), top, egui::Color32::BLACK));
+                }
+            }
+            ui.painter().extend(shapes);
+
+            let cell_at = |pos: egui::Pos2| -> Option<(usize, usize)> {
+                let local = pos - grid_min;
+                if local.x < 0.0 || local.y < 0.0 {
+                    return None;
+                }
+                let (r, c) = ((local.y / cell) as usize, (local.x / cell) as usize);
+                (r < n && c < n).then(|| (matrix.order[r], matrix.order[c]))
+            };
+            if let Some((fi, fj)) = resp.hover_pos().and_then(cell_at) {
+                egui::show_tooltip_at_pointer(ui.ctx(), egui::Id::new("similarity_tip"), |ui| {
+                    ui.label(format!("#{} {}", fi, self.files[fi].file_name));
+                    ui.label(format!("#{} {}", fj, self.files[fj].file_name));
+                    ui.label(format!("Distance: {}", matrix.distances[fi][fj]));
+                });
+            }
+            if resp.clicked()
+                && let Some((fi, fj)) = resp.interact_pointer_pos().and_then(cell_at)
+                && fi != fj
+            {
+                self.pair_diff = Some((fi, fj));
+            }
+        });
+
+        // Legend in cluster order
+        ui.separator();
+        egui::ScrollArea::vertical().id_source("similarity_legend").max_height(160.0).show(ui, |ui| {
+            for &fi in &matrix.order {
+                ui.monospace(format!("#{:<3} {}", fi, self.files[fi].file_name));
+            }
+        });
+    }
+
+    pub(crate) fn show_pair_diff_window(&mut self, ctx: &egui::Context) {
+        let Some((a, b)) = self.pair_diff else { return };
+        if a >= self.files.len() || b >= self.files.len() {
+            self.pair_diff = None;
+            return;
+        }
+        let main_rect = ctx.input(|i| i.screen_rect());
+        let mut open = true;
+        egui::Window::new(format!("Diff: {} vs {}", self.files[a].file_name, self.files[b].file_name))
+            .id(egui::Id::new("pair_diff_window"))
+            .constrain_to(main_rect)
+            .max_size(main_rect.size())
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let (fa, fb) = (&self.files[a], &self.files[b]);
+                let diffs: Vec<u64> = self
+                    .intersect_addresses
+                    .iter()
+                    .copied()
+                    .filter(|addr| {
+                        let va = fa.address_to_data.get(addr).map(String::as_str).unwrap_or("");
+                        let vb = fb.address_to_data.get(addr).map(String::as_str).unwrap_or("");
+                        cell_distance(va, vb, DistanceMetric::Bytes) > 0
+                    })
+                    .collect();
+                ui.label(format!("{} of {} addresses differ", diffs.len(), self.intersect_addresses.len()));
+                ui.separator();
+                TableBuilder::new(ui)
+                    .striped(true)
+                    .column(Column::initial(140.0).resizable(true))
+                    .column(Column::initial(160.0).resizable(true))
+                    .column(Column::remainder())
+                    .header(24.0, |mut header| {
+                        header.col(|ui| { ui.label("Address"); });
+                        header.col(|ui| { ui.add(egui::Label::new(&fa.file_name).wrap(true)); });
+                        header.col(|ui| { ui.add(egui::Label::new(&fb.file_name).wrap(true)); });
+                    })
+                    .body(|body| {
+                        body.rows(22.0, diffs.len(), |mut row| {
+                            let addr = diffs[row.index()];
+                            row.col(|ui| { ui.label(format_addr(addr)); });
+                            for pf in [fa, fb] {
+                                row.col(|ui| {
+                                    let raw = pf.address_to_data.get(&addr).cloned().unwrap_or_default();
+                                    ui.colored_label(egui::Color32::RED, format_data_with_base(&raw, self.display_base));
+                                });
+                            }
+                        });
+                    });
+            });
+        if !open {
+            self.pair_diff = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsed_file;
+
+    #[test]
+    fn counts_differing_bytes_and_bits() {
+        let files = [parsed_file(&[(0, "0F"), (1, "AA"), (2, "x")]), parsed_file(&[(0, "0x0F"), (1, "AB"), (2, "y")]), parsed_file(&[(0, "F0"), (1, "AA")])];
+        let bytes = pairwise_distances(&files, &[0, 1, 2], DistanceMetric::Bytes);
+        assert_eq!(bytes, [vec![0, 2, 2], vec![2, 0, 3], vec![2, 3, 0]]);
+        let bits = pairwise_distances(&files, &[0, 1, 2], DistanceMetric::Bits);
+        assert_eq!(bits, [vec![0, 9, 16], vec![9, 0, 17], vec![16, 17, 0]]);
+    }
+
+    #[test]
+    fn clusters_keep_close_files_together() {
+        // 0 and 2 are close, 1 and 3 are close, the two pairs are far apart
+        let distances = vec![vec![0, 10, 1, 9], vec![10, 0, 11, 2], vec![1, 11, 0, 10], vec![9, 2, 10, 0]];
+        assert_eq!(cluster_order(&distances), [0, 2, 1, 3]);
+        assert_eq!(cluster_order(&[vec![0]]), [0]);
+        assert!(cluster_order(&[]).is_empty());
+    }
+}
@@ -0,0 +1,235 @@
+use eframe::egui;
+use log::{info, warn};
+use regex::Regex;
+use std::cmp::Ordering;
+use std::time::UNIX_EPOCH;
+
+use crate::{AppState, ParsedFile, format_addr, format_data_with_base, parse_data_value};
+
+// Where the timestamp used to order files comes from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub(crate) enum TimeSource {
+    #[default]
+    Modified,
+    FileName,
+    Header,
+}
+
+// Sortable timestamp: digit-only stamps (after dropping separators) compare numerically
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum TimeKey {
+    Number(u128),
+    Text(String),
+}
+
+impl std::fmt::Display for TimeKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeKey::Number(n) => write!(f, "{}", n),
+            TimeKey::Text(t) => write!(f, "{}", t),
+        }
+    }
+}
+
+fn normalize_key(text: &str) -> Option<TimeKey> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    let digits: String = text.chars().filter(|c| !matches!(c, '-' | '_' | ':' | '.' | '/' | 'T' | ' ')).collect();
+    match digits.parse::<u128>() {
+        Ok(n) if digits.chars().all(|c| c.is_ascii_digit()) => Some(TimeKey::Number(n)),
+        _ => Some(TimeKey::Text(text.to_string())),
+    }
+}
+
+pub(crate) fn time_key(pf: &ParsedFile, source: TimeSource, name_pattern: Option<&Regex>, header_key: &str) -> Option<TimeKey> {
+    match source {
+        TimeSource::Modified => pf
+            .modified
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| TimeKey::Number(d.as_nanos())),
+        TimeSource::FileName => {
+            let caps = name_pattern?.captures(&pf.file_name)?;
+            // First capture group if the pattern has one, otherwise the whole match
+            let m = caps.get(1).or_else(|| caps.get(0))?;
+            normalize_key(m.as_str())
+        }
+        TimeSource::Header => {
+            let key = header_key.trim();
+            if key.is_empty() {
+                return None;
+            }
+            pf.header_lines.iter().find_map(|line| {
+                let pos = line.to_ascii_lowercase().find(&key.to_ascii_lowercase())?;
+                let rest = line[pos + key.len()..].trim_start_matches([' ', ':', '=', '\t']);
+                normalize_key(rest)
+            })
+        }
+    }
+}
+
+impl AppState {
+    fn trend_regex(&self) -> Result<Regex, regex::Error> {
+        Regex::new(&self.trend_name_pattern)
+    }
+
+    // Goes through reorder_files so the earlier undo steps, which hold file indices, stay valid
+    fn sort_files_by_time(&mut self) {
+        let regex = self.trend_regex().ok();
+        let mut keyed: Vec<(Option<TimeKey>, usize)> = self
+            .files
+            .iter()
+            .enumerate()
+            .map(|(idx, pf)| (time_key(pf, self.trend_source, regex.as_ref(), &self.trend_header_key), idx))
+            .collect();
+        let missing = keyed.iter().filter(|(k, _)| k.is_none()).count();
+        if missing > 0 {
+            warn!("{} file(s) have no timestamp and were moved to the end", missing);
+        }
+        // Stable sort keeps load order among equal or missing keys
+        keyed.sort_by(|a, b| match (&a.0, &b.0) {
+            (Some(x), Some(y)) => x.cmp(y),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        });
+        let order: Vec<usize> = keyed.into_iter().map(|(_, idx)| idx).collect();
+        info!("Sorted {} files by {:?}", self.files.len(), self.trend_source);
+        self.reorder_files(order);
+    }
+
+    pub(crate) fn show_trend_window(&mut self, ctx: &egui::Context) {
+        let main_rect = ctx.input(|i| i.screen_rect());
+        let mut open = self.show_trend;
+        egui::Window::new("Trend")
+            .constrain_to(main_rect)
+            .max_size(main_rect.size())
+            .open(&mut open)
+            .show(ctx, |ui| self.trend_ui(ui));
+        self.show_trend = open;
+    }
+
+    fn trend_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Order by:");
+            ui.radio_value(&mut self.trend_source, TimeSource::Modified, "File mtime");
+            ui.radio_value(&mut self.trend_source, TimeSource::FileName, "File name pattern");
+            ui.radio_value(&mut self.trend_source, TimeSource::Header, "Header line");
+        });
+        let mut pattern_ok = true;
+        match self.trend_source {
+            TimeSource::Modified => {}
+            TimeSource::FileName => {
+                ui.horizontal(|ui| {
+                    ui.label("Regex:");
+                    ui.add(egui::TextEdit::singleline(&mut self.trend_name_pattern).desired_width(240.0));
+                });
+                if let Err(e) = self.trend_regex() {
+                    pattern_ok = false;
+                    ui.colored_label(egui::Color32::RED, format!("Invalid pattern: {}", e));
+                }
+            }
+            TimeSource::Header => {
+                ui.horizontal(|ui| {
+                    ui.label("Header key:");
+                    ui.add(egui::TextEdit::singleline(&mut self.trend_header_key).desired_width(120.0));
+                });
+            }
+        }
+        if ui.add_enabled(pattern_ok && !self.files.is_empty(), egui::Button::new("Sort Files by Time")).clicked() {
+            self.sort_files_by_time();
+        }
+
+        let regex = self.trend_regex().ok();
+        egui::CollapsingHeader::new("Ordering keys").show(ui, |ui| {
+            egui::ScrollArea::vertical().id_source("trend_keys").max_height(120.0).show(ui, |ui| {
+                for (idx, pf) in self.files.iter().enumerate() {
+                    let key = time_key(pf, self.trend_source, regex.as_ref(), &self.trend_header_key);
+                    let key_text = key.map_or_else(|| "(none)".to_string(), |k| k.to_string());
+                    ui.monospace(format!("#{:<3} {}  {}", idx, key_text, pf.file_name));
+                }
+            });
+        });
+
+        ui.separator();
+        let Some(addr) = self.selected_addr() else {
+            ui.label("Tip: click a row (Address column) to select.");
+            return;
+        };
+        ui.label(format!("Selected Address: {}", format_addr(addr)));
+        let shown: Vec<String> = self
+            .files
+            .iter()
+            .map(|pf| format_data_with_base(&pf.address_to_data.get(&addr).cloned().unwrap_or_default(), self.display_base))
+            .collect();
+        let values: Vec<Option<u64>> = self
+            .files
+            .iter()
+            .map(|pf| pf.address_to_data.get(&addr).and_then(|raw| parse_data_value(raw)))
+            .collect();
+        // A change point is a file whose value differs from the previous file in order
+        let changes: Vec<usize> = (1..shown.len()).filter(|&i| shown[i] != shown[i - 1]).collect();
+        self.trend_plot(ui, &values, &shown, &changes);
+
+        ui.label(format!("Change points: {}", changes.len()));
+        egui::ScrollArea::vertical().id_source("trend_changes").max_height(120.0).show(ui, |ui| {
+            for &i in &changes {
+                ui.monospace(format!("#{:<3} {}: {} → {}", i, self.files[i].file_name, shown[i - 1], shown[i]));
+            }
+        });
+    }
+
+    fn trend_plot(&self, ui: &mut egui::Ui, values: &[Option<u64>], shown: &[String], changes: &[usize]) {
+        let desired = egui::vec2(ui.available_width().max(320.0), 220.0);
+        let (rect, resp) = ui.allocate_exact_size(desired, egui::Sense::hover());
+        let plot = egui::Rect::from_min_max(rect.min + egui::vec2(56.0, 8.0), rect.max - egui::vec2(8.0, 20.0));
+        let n = values.len();
+        let numeric: Vec<u64> = values.iter().flatten().copied().collect();
+        let (Some(&lo), Some(&hi)) = (numeric.iter().min(), numeric.iter().max()) else {
+            ui.label("No numeric values at this address.");
+            return;
+        };
+        let (lo, hi) = if lo == hi { (lo.saturating_sub(1) as f32, hi as f32 + 1.0) } else { (lo as f32, hi as f32) };
+        let x_at = |i: usize| if n <= 1 { plot.center().x } else { plot.left() + plot.width() * i as f32 / (n - 1) as f32 };
+        let y_at = |v: u64| plot.bottom() - plot.height() * (v as f32 - lo) / (hi - lo);
+
+        let painter = ui.painter();
+        let axis = egui::Stroke::new(1.0, egui::Color32::GRAY);
+        painter.line_segment([plot.left_bottom(), plot.right_bottom()], axis);
+        painter.line_segment([plot.left_bottom(), plot.left_top()], axis);
+        let font = egui::FontId::monospace(10.0);
+        painter.text(egui::pos2(plot.left() - 4.0, plot.top()), egui::Align2::RIGHT_TOP, format!("{}", hi), font.clone(), egui::Color32::BLACK);
+        painter.text(egui::pos2(plot.left() - 4.0, plot.bottom()), egui::Align2::RIGHT_BOTTOM, format!("{}", lo), font.clone(), egui::Color32::BLACK);
+        painter.text(egui::pos2(plot.left(), rect.bottom()), egui::Align2::LEFT_BOTTOM, "#0", font.clone(), egui::Color32::BLACK);
+        painter.text(egui::pos2(plot.right(), rect.bottom()), egui::Align2::RIGHT_BOTTOM, format!("#{}", n.saturating_sub(1)), font, egui::Color32::BLACK);
+
+        let red = egui::Stroke::new(1.0, egui::Color32::RED);
+        for &i in changes {
+            let x = x_at(i);
+            painter.extend(egui::Shape::dashed_line(&[egui::pos2(x, plot.top()), egui::pos2(x, plot.bottom())], red, 4.0, 3.0));
+        }
+        let points: Vec<egui::Pos2> = values
+            .iter()
+            .enumerate()
+            .filter_map(|(i, v)| v.map(|v| egui::pos2(x_at(i), y_at(v))))
+            .collect();
+        painter.add(egui::Shape::line(points.clone(), egui::Stroke::new(1.5, egui::Color32::from_rgb(40, 110, 220))));
+        for (i, v) in values.iter().enumerate() {
+            if let Some(v) = v {
+                let color = if changes.contains(&i) { egui::Color32::RED } else { egui::Color32::from_rgb(40, 110, 220) };
+                painter.circle_filled(egui::pos2(x_at(i), y_at(*v)), 3.0, color);
+            }
+        }
+
+        if let Some(pos) = resp.hover_pos()
+            && n > 0
+        {
+            let i = if n == 1 { 0 } else { (((pos.x - plot.left()) / plot.width()) * (n - 1) as f32).round().clamp(0.0, (n - 1) as f32) as usize };
+            egui::show_tooltip_at_pointer(ui.ctx(), egui::Id::new("trend_tip"), |ui| {
+                ui.label(format!("#{} {}", i, self.files[i].file_name));
+                ui.monospace(&shown[i]);
+            });
+        }
+    }
+}
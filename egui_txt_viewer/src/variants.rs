@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::{AppState, DisplayBase, ParsedFile, format_data_with_base};
+
+// Files whose compared data is identical
+pub(crate) struct Variant {
+    pub(crate) hash: u64,
+    // File indices in load order
+    pub(crate) members: Vec<usize>,
+}
+
+pub(crate) struct Variants {
+    revision: u64,
+    // Ordered by first member
+    pub(crate) list: Vec<Variant>,
+}
+
+// Column of the main table: a single file, or a collapsed variant shown through its first member
+pub(crate) struct TableColumn {
+    pub(crate) file_idx: usize,
+    pub(crate) members: Vec<usize>,
+    // (1-based variant number, variant hash) for files that belong to a multi-file variant
+    pub(crate) variant: Option<(usize, u64)>,
+}
+
+impl TableColumn {
+    pub(crate) fn collapsed(&self) -> bool {
+        self.members.len() > 1
+    }
+}
+
+// A file's data over the compared addresses, normalized so 7E, 0x7E and 7Eh agree
+fn normalized_data(pf: &ParsedFile, addrs: &[u64]) -> Vec<String> {
+    addrs.iter().map(|addr| format_data_with_base(pf.address_to_data.get(addr).map(String::as_str).unwrap_or(""), DisplayBase::Hex)).collect()
+}
+
+fn data_hash(data: &[String]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl Variants {
+    fn compute(files: &[ParsedFile], addrs: &[u64], revision: u64) -> Self {
+        let mut list: Vec<Variant> = Vec::new();
+        // The hash only buckets files; membership is confirmed against the data of the variant's first file
+        let mut by_hash: HashMap<u64, Vec<(usize, Vec<String>)>> = HashMap::new();
+        for (idx, pf) in files.iter().enumerate() {
+            let data = normalized_data(pf, addrs);
+            let hash = data_hash(&data);
+            let bucket = by_hash.entry(hash).or_default();
+            match bucket.iter().find(|(_, first)| *first == data) {
+                Some(&(v, _)) => list[v].members.push(idx),
+                None => {
+                    bucket.push((list.len(), data));
+                    list.push(Variant { hash, members: vec![idx] });
+                }
+            }
+        }
+        Self { revision, list }
+    }
+}
+
+impl AppState {
+    pub(crate) fn refresh_variants(&mut self) {
+        if !self.unique_variants {
+            return;
+        }
+        if self.variants.as_ref().is_none_or(|v| v.revision != self.data_revision) {
+            self.variants = Some(Variants::compute(&self.files, &self.intersect_addresses, self.data_revision));
+        }
+    }
+
+    // Data columns to show: every file, or one per variant unless the variant is expanded
+    pub(crate) fn table_columns(&self) -> Vec<TableColumn> {
+        let single = |file_idx: usize, variant| TableColumn { file_idx, members: vec![file_idx], variant };
+        let Some(variants) = self.variants.as_ref().filter(|_| self.unique_variants) else {
+            return (0..self.files.len()).map(|idx| single(idx, None)).collect();
+        };
+        let mut columns = Vec::new();
+        for (n, v) in variants.list.iter().enumerate() {
+            if v.members.len() == 1 {
+                columns.push(single(v.members[0], None));
+            } else if self.expanded_variants.contains(&v.hash) {
+                columns.extend(v.members.iter().map(|&idx| single(idx, Some((n + 1, v.hash)))));
+            } else {
+                columns.push(TableColumn { file_idx: v.members[0], members: v.members.clone(), variant: Some((n + 1, v.hash)) });
+            }
+        }
+        columns
+    }
+
+    pub(crate) fn toggle_variant(&mut self, hash: u64) {
+        if !self.expanded_variants.remove(&hash) {
+            self.expanded_variants.insert(hash);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsed_file;
+
+    #[test]
+    fn groups_files_with_equal_data() {
+        let files = [parsed_file(&[(0, "7E"), (1, "01")]), parsed_file(&[(0, "0x7E"), (1, "01h")]), parsed_file(&[(0, "7E"), (1, "02")]), parsed_file(&[(0, "7e"), (1, "1")])];
+        let variants = Variants::compute(&files, &[0, 1], 1);
+        let members: Vec<_> = variants.list.iter().map(|v| v.members.clone()).collect();
+        assert_eq!(members, [vec![0, 1, 3], vec![2]]);
+        assert_ne!(variants.list[0].hash, variants.list[1].hash);
+    }
+
+    #[test]
+    fn only_compared_addresses_count() {
+        let files = [parsed_file(&[(0, "01"), (1, "AA")]), parsed_file(&[(0, "01"), (1, "BB")]), parsed_file(&[(1, "AA")])];
+        let variants = Variants::compute(&files, &[0], 1);
+        let members: Vec<_> = variants.list.iter().map(|v| v.members.clone()).collect();
+        assert_eq!(members, [vec![0, 1], vec![2]]);
+    }
+}